@@ -45,5 +45,5 @@ mod_code="mod $dayname;"
 sed -i '' "s/^[[:space:]]*pub fn run_day/$mod_code\n&/" "$mod_file"
 
 # Secondly, insert case code before "_ =>"
-case_code="        $day => $dayname::$func_name(input, part_two),"
+case_code="        $day => $dayname::$func_name(input, part_two, args),"
 sed -i '' "s/^[[:space:]]*_ =/$case_code\n&/" "$mod_file" 
\ No newline at end of file
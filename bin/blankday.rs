@@ -1,3 +1,7 @@
-pub fn day_replace(input: &String, _part_two: bool) -> String {
-    "day_replace".to_string()
+use crate::answer::Answer;
+use crate::helpers::Args;
+use crate::parsing::ParseResult;
+
+pub fn day_replace(input: &str, _part_two: bool, _args: &Args) -> ParseResult<Answer> {
+    Ok(Answer::Unsolved("day_replace is not implemented yet".to_string()))
 }
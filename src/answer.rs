@@ -0,0 +1,152 @@
+use serde_json::{json, Value};
+use std::fmt;
+
+// The result of running a solution. Most answers are numbers or short strings,
+// but some puzzles draw their answer, and some inputs have no answer at all.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Answer {
+    Int(i64),
+    Text(String),
+    // Multi-line output (like letters drawn on a screen) which needs a human to read it.
+    Art(String),
+    // The solution ran, but couldn't find an answer. Includes the reason why.
+    Unsolved(String),
+}
+
+impl Answer {
+    // True if there's something worth submitting or comparing.
+    pub fn is_solved(&self) -> bool {
+        match self {
+            Answer::Int(_) => true,
+            Answer::Text(text) | Answer::Art(text) => !text.trim().is_empty(),
+            Answer::Unsolved(_) => false,
+        }
+    }
+
+    // A short name for the type of answer, used in machine-readable output.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Answer::Int(_) => "int",
+            Answer::Text(_) => "text",
+            Answer::Art(_) => "art",
+            Answer::Unsolved(_) => "unsolved",
+        }
+    }
+
+    // Integers stay numbers in JSON, and an unsolved answer is null.
+    pub fn to_json(&self) -> Value {
+        match self {
+            Answer::Int(n) => json!(n),
+            Answer::Text(text) | Answer::Art(text) => json!(text),
+            Answer::Unsolved(_) => Value::Null,
+        }
+    }
+
+    // The value to send to Advent of Code. Art can't be submitted directly,
+    // since the site expects the letters it spells out.
+    pub fn submission(&self) -> Option<String> {
+        match self {
+            Answer::Int(n) => Some(n.to_string()),
+            Answer::Text(text) if !text.trim().is_empty() => Some(text.trim().to_string()),
+            _ => None,
+        }
+    }
+
+    // Compares against an answer saved as text, such as one read from disk.
+    pub fn matches(&self, stored: &str) -> bool {
+        match self {
+            Answer::Unsolved(_) => false,
+            Answer::Art(art) => normalize_art(art) == normalize_art(stored),
+            _ => self.submission().as_deref() == Some(stored.trim()),
+        }
+    }
+}
+
+// Trailing whitespace on each line isn't meaningful in drawn answers.
+fn normalize_art(art: &str) -> String {
+    art.trim_matches('\n')
+        .lines()
+        .map(|line| line.trim_end())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+impl fmt::Display for Answer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Answer::Int(n) => write!(f, "{}", n),
+            Answer::Text(text) => write!(f, "{}", text),
+            Answer::Art(art) => write!(f, "\n{}", art),
+            Answer::Unsolved(reason) => write!(f, "no solution ({})", reason),
+        }
+    }
+}
+
+// Numbers too large for an i64 are kept exactly as text.
+macro_rules! impl_from_int {
+    ($($t:ty),*) => {
+        $(
+            impl From<$t> for Answer {
+                fn from(n: $t) -> Answer {
+                    match i64::try_from(n) {
+                        Ok(n) => Answer::Int(n),
+                        Err(_) => Answer::Text(n.to_string()),
+                    }
+                }
+            }
+        )*
+    };
+}
+
+impl_from_int!(i8, i16, i32, i64, i128, u8, u16, u32, u64, u128, usize);
+
+impl From<String> for Answer {
+    fn from(text: String) -> Answer {
+        Answer::Text(text)
+    }
+}
+
+impl From<&str> for Answer {
+    fn from(text: &str) -> Answer {
+        Answer::Text(text.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_ints() {
+        assert_eq!(Answer::from(42u32), Answer::Int(42));
+        assert_eq!(Answer::from(-3i32), Answer::Int(-3));
+        assert_eq!(Answer::from(u128::MAX), Answer::Text(u128::MAX.to_string()));
+    }
+
+    #[test]
+    fn test_matches() {
+        assert!(Answer::Int(24933642).matches("24933642\n"));
+        assert!(Answer::from("CMZ").matches("CMZ"));
+        assert!(!Answer::from("CMZ").matches("MCD"));
+        assert!(Answer::Art("#..#  \n.##.\n".to_string()).matches("\n#..#\n.##."));
+        assert!(!Answer::Unsolved("no path".to_string()).matches(""));
+    }
+
+    #[test]
+    fn test_to_json() {
+        assert_eq!(Answer::Int(7).to_json(), json!(7));
+        assert_eq!(Answer::from("CMZ").to_json(), json!("CMZ"));
+        assert_eq!(
+            Answer::Unsolved("no path".to_string()).to_json(),
+            Value::Null
+        );
+    }
+
+    #[test]
+    fn test_submission() {
+        assert_eq!(Answer::Int(7).submission(), Some("7".to_string()));
+        assert_eq!(Answer::Art("#".to_string()).submission(), None);
+        assert_eq!(Answer::from("").submission(), None);
+        assert!(!Answer::from("  ").is_solved());
+    }
+}
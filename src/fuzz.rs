@@ -0,0 +1,369 @@
+// A small fuzzer for everything that reads input: each day's parser and
+// solver, plus the history file, the cached leaderboard JSON and stored answers. It starts from
+// a corpus of valid inputs and mangles them in ways that tend to upset parsers
+// (flipped bytes, huge numbers, missing or repeated lines...). A parser can
+// reject anything it likes, but a panic is always a bug.
+//
+// The corpus for each target is its built-in seeds, plus every file in
+// fuzz/corpus/<target>/. Inputs that crash are saved to fuzz/crashes/<target>/;
+// once they're fixed, move them into the corpus so they stay fixed.
+use crate::answer::Answer;
+use crate::history;
+use crate::leaderboard;
+use crate::parsing::ParseResult;
+use crate::rng::Rng;
+use crate::y2022;
+use std::fs;
+use std::panic::{self, AssertUnwindSafe};
+use std::path::Path;
+
+pub const CORPUS_DIR: &str = "fuzz/corpus";
+pub const CRASH_DIR: &str = "fuzz/crashes";
+
+// Parses (and throws away) one input. Returning an error is fine.
+pub type Parser = fn(&str) -> ParseResult<()>;
+
+// Like a parser, but can hold on to whatever else it needs, like a day number.
+type Run = Box<dyn Fn(&str) -> ParseResult<()>>;
+
+pub struct Target {
+    pub name: String,
+    run: Run,
+    // Inputs to start from, on top of the corpus directory.
+    seeds: Vec<String>,
+}
+
+impl Target {
+    pub fn new(
+        name: String,
+        run: impl Fn(&str) -> ParseResult<()> + 'static,
+        seeds: Vec<String>,
+    ) -> Target {
+        Target {
+            name,
+            run: Box::new(run),
+            seeds,
+        }
+    }
+
+    // Returns the panic message if the parser panicked.
+    pub fn check(&self, input: &str) -> Result<(), String> {
+        panic::catch_unwind(AssertUnwindSafe(|| {
+            let _ = (self.run)(input);
+        }))
+        .map_err(|payload| {
+            if let Some(message) = payload.downcast_ref::<&str>() {
+                message.to_string()
+            } else if let Some(message) = payload.downcast_ref::<String>() {
+                message.clone()
+            } else {
+                "panicked".to_string()
+            }
+        })
+    }
+
+    // The seeds, plus the corpus and any saved crashes on disk.
+    pub fn corpus(&self) -> Vec<String> {
+        let mut corpus = self.seeds.clone();
+        for dir in [CORPUS_DIR, CRASH_DIR] {
+            let Ok(files) = fs::read_dir(Path::new(dir).join(&self.name)) else {
+                continue;
+            };
+            let mut paths: Vec<_> = files.flatten().map(|file| file.path()).collect();
+            paths.sort();
+            for path in paths {
+                if let Ok(bytes) = fs::read(&path) {
+                    let input = String::from_utf8_lossy(&bytes).into_owned();
+                    if !corpus.contains(&input) {
+                        corpus.push(input);
+                    }
+                }
+            }
+        }
+        corpus
+    }
+}
+
+pub fn targets() -> Vec<Target> {
+    let mut targets = y2022::fuzz::targets();
+    targets.push(Target::new(
+        "history".to_string(),
+        |input| {
+            let (entries, _) = history::parse_history(input);
+            history::render_stats(&entries, None);
+            Ok(())
+        },
+        // Built in, so the target still has something to start from when it's
+        // run from outside the repo.
+        vec![include_str!("../fuzz/corpus/history/example.jsonl").to_string()],
+    ));
+    targets.push(Target::new(
+        "leaderboard".to_string(),
+        |input| {
+            let leaderboard = leaderboard::parse_leaderboard(input)?;
+            leaderboard::render(&leaderboard);
+            Ok(())
+        },
+        vec![include_str!("../fuzz/corpus/leaderboard/example.json").to_string()],
+    ));
+    targets.push(Target::new(
+        "stored_answer".to_string(),
+        |input| {
+            let answers = [
+                Answer::Int(-3),
+                Answer::Text("CMZ".to_string()),
+                Answer::Art("#..#\n####\n".to_string()),
+                Answer::Unsolved(String::new()),
+            ];
+            for answer in answers {
+                answer.matches(input);
+            }
+            Ok(())
+        },
+        vec![
+            "45000\n".to_string(),
+            "  CMZ ".to_string(),
+            "#..#   \n####".to_string(),
+        ],
+    ));
+    targets
+}
+
+pub struct Crash {
+    pub input: String,
+    pub message: String,
+}
+
+// Runs the whole corpus, then `iterations` mutated inputs. Stops at the first
+// crash, shrunk down to make it easier to see what went wrong.
+pub fn fuzz(target: &Target, iterations: usize, rng: &mut Rng) -> Option<Crash> {
+    let mut corpus = target.corpus();
+    // Mutations need something to start from, even if it's nothing at all.
+    if corpus.is_empty() {
+        corpus.push(String::new());
+    }
+    let mut inputs = corpus.clone().into_iter();
+    let mut tried = 0;
+    loop {
+        let input = match inputs.next() {
+            Some(input) => input,
+            None if tried < iterations => {
+                tried += 1;
+                let base = rng.index(corpus.len());
+                mutate(&corpus[base], &corpus, rng)
+            }
+            None => return None,
+        };
+        if let Err(message) = target.check(&input) {
+            return Some(shrink(target, input, message));
+        }
+    }
+}
+
+// Bits of input that often appear in puzzles, or that are likely to break
+// something: boundary numbers, separators and the days' own keywords.
+const TOKENS: &[&str] = &[
+    "\n",
+    "\n\n",
+    " ",
+    "  ",
+    ",",
+    "-",
+    "0",
+    "1",
+    "-1",
+    "9",
+    "255",
+    "256",
+    "2147483647",
+    "-2147483648",
+    "4294967295",
+    "4294967296",
+    "9223372036854775807",
+    "-9223372036854775808",
+    "18446744073709551615",
+    "18446744073709551616",
+    "99999999999999999999999",
+    "[",
+    "]",
+    "[A]",
+    "   ",
+    "$ cd ",
+    "$ cd ..",
+    "$ cd /",
+    "$ ls",
+    "dir ",
+    "move ",
+    " from ",
+    " to ",
+    "addx ",
+    "noop",
+    "Monkey ",
+    "old",
+    " * ",
+    " + ",
+    "S",
+    "E",
+    "a",
+    "z",
+    "R ",
+    "U ",
+    "\r\n",
+    "{",
+    "}",
+    "\"",
+    ":",
+    "null",
+    "\u{e9}",
+    "\u{1f384}",
+];
+
+pub fn mutate(input: &str, corpus: &[String], rng: &mut Rng) -> String {
+    let mut bytes = input.as_bytes().to_vec();
+    for _ in 0..rng.range(1, 4) {
+        let at = rng.index(bytes.len() + 1);
+        match rng.below(8) {
+            // Flip a bit.
+            0 if at < bytes.len() => bytes[at] ^= 1 << rng.below(8),
+            // Insert a token.
+            1 => {
+                let token = rng.pick(TOKENS).as_bytes();
+                bytes.splice(at..at, token.iter().copied());
+            }
+            // Delete a chunk.
+            2 => {
+                let end = (at + rng.index(16) + 1).min(bytes.len());
+                bytes.drain(at.min(end)..end);
+            }
+            // Repeat a chunk.
+            3 => {
+                let end = (at + rng.index(32) + 1).min(bytes.len());
+                let chunk = bytes[at.min(end)..end].to_vec();
+                bytes.splice(
+                    at..at,
+                    chunk
+                        .iter()
+                        .copied()
+                        .cycle()
+                        .take(chunk.len() * rng.index(4)),
+                );
+            }
+            // Cut it short.
+            4 => bytes.truncate(at),
+            // Swap in the end of another input.
+            5 => {
+                let other = rng.pick(corpus).as_bytes();
+                let from = rng.index(other.len() + 1);
+                bytes.truncate(at);
+                bytes.extend_from_slice(&other[from..]);
+            }
+            // Swap the next number for one of the tokens' numbers, which
+            // finds overflows that mangling digits one at a time won't.
+            6 => {
+                let Some(start) = bytes[at..].iter().position(u8::is_ascii_digit) else {
+                    continue;
+                };
+                let start = at + start;
+                let len = bytes[start..]
+                    .iter()
+                    .take_while(|b| b.is_ascii_digit())
+                    .count();
+                let numbers: Vec<&str> = TOKENS
+                    .iter()
+                    .copied()
+                    .filter(|token| token.parse::<i128>().is_ok())
+                    .collect();
+                let number = rng.pick(&numbers);
+                bytes.splice(start..start + len, number.bytes());
+            }
+            // Overwrite a byte with any value at all.
+            _ if at < bytes.len() => bytes[at] = rng.below(256) as u8,
+            _ => bytes.push(rng.below(256) as u8),
+        }
+    }
+    // Inputs are always read as text, so invalid UTF-8 never reaches a parser.
+    String::from_utf8_lossy(&bytes).into_owned()
+}
+
+// Removes lines, then characters, for as long as the input still crashes.
+fn shrink(target: &Target, input: String, message: String) -> Crash {
+    let mut crash = Crash { input, message };
+    let mut lines: Vec<&str> = crash.input.split_inclusive('\n').collect();
+    let mut i = 0;
+    while i < lines.len() {
+        let mut fewer = lines.clone();
+        fewer.remove(i);
+        if let Err(message) = target.check(&fewer.concat()) {
+            lines = fewer;
+            crash.message = message;
+        } else {
+            i += 1;
+        }
+    }
+    let mut chars: Vec<char> = lines.concat().chars().collect();
+    let mut i = 0;
+    while i < chars.len() {
+        let mut fewer = chars.clone();
+        fewer.remove(i);
+        if let Err(message) = target.check(&fewer.iter().collect::<String>()) {
+            chars = fewer;
+            crash.message = message;
+        } else {
+            i += 1;
+        }
+    }
+    crash.input = chars.into_iter().collect();
+    crash
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parsers_dont_panic() {
+        for target in targets() {
+            assert!(!target.corpus().is_empty(), "{} has no corpus", target.name);
+            if let Some(crash) = fuzz(&target, 300, &mut Rng::new(1)) {
+                panic!(
+                    "{} panicked ({}) on input {:?}",
+                    target.name, crash.message, crash.input
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_empty_corpus() {
+        let target = Target::new("test".to_string(), |_| Ok(()), Vec::new());
+        assert!(fuzz(&target, 10, &mut Rng::new(1)).is_none());
+    }
+
+    #[test]
+    fn test_crashes_are_shrunk() {
+        let target = Target::new(
+            "test".to_string(),
+            |input| {
+                assert!(!input.contains('x'), "found an x");
+                Ok(())
+            },
+            vec!["abc\ndxf\nghi\n".to_string()],
+        );
+        let crash = fuzz(&target, 0, &mut Rng::new(1)).unwrap();
+        assert_eq!(crash.input, "x");
+        assert_eq!(crash.message, "found an x");
+    }
+
+    #[test]
+    fn test_mutate() {
+        let corpus = vec!["move 1 from 2 to 3\n".to_string()];
+        let mut rng = Rng::new(7);
+        let mutated: Vec<String> = (0..50)
+            .map(|_| mutate(&corpus[0], &corpus, &mut rng))
+            .collect();
+        assert!(mutated.iter().any(|input| *input != corpus[0]));
+        // The same seed always mangles the same way.
+        let mut rng = Rng::new(7);
+        assert_eq!(mutate(&corpus[0], &corpus, &mut rng), mutated[0]);
+    }
+}
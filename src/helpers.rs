@@ -1,79 +1,339 @@
+use crate::answer::Answer;
 use crate::AOC_YEAR;
 use reqwest::header::COOKIE;
+use std::env;
 use std::fs;
 use std::io::{stdin, stdout, Read, Write};
 use std::process;
+use std::str::FromStr;
+use std::time::SystemTime;
 
+// Handy for stepping through a solution while debugging.
+#[allow(dead_code)]
 pub fn pause() {
     let mut stdout = stdout();
-    stdout.write(b"Press Enter to continue...").unwrap();
+    stdout.write_all(b"Press Enter to continue...").unwrap();
     stdout.flush().unwrap();
-    stdin().read(&mut [0]).unwrap();
+    stdin().read_exact(&mut [0]).unwrap();
+}
+
+// Command line options. main reads them once and hands them to the days, so
+// a day never looks at the process's own arguments and can be run from bench,
+// fuzz or a test with whatever options it needs (usually none).
+#[derive(Debug, Clone, Default)]
+pub struct Args(Vec<String>);
+
+impl Args {
+    pub fn from_env() -> Args {
+        Args(env::args().collect())
+    }
+
+    #[cfg(test)]
+    pub fn new(args: &[&str]) -> Args {
+        Args(args.iter().map(|arg| arg.to_string()).collect())
+    }
+
+    pub fn has(&self, arg: &str) -> bool {
+        self.0.iter().any(|a| a == arg)
+    }
+
+    // Returns the value following an argument, e.g. "json" for "--format json".
+    pub fn value(&self, arg: &str) -> Option<&str> {
+        let position = self.0.iter().position(|a| a == arg)?;
+        self.0.get(position + 1).map(String::as_str)
+    }
+
+    // Like value, but parsed into a number or similar. A value which doesn't
+    // parse is an error rather than being ignored, since it's a typo.
+    pub fn parsed<T: FromStr>(&self, arg: &str) -> Result<Option<T>, String> {
+        match self.value(arg) {
+            None => Ok(None),
+            Some(value) => value
+                .parse::<T>()
+                .map(Some)
+                .map_err(|_| format!("invalid value for {}: {}", arg, value)),
+        }
+    }
+}
+
+pub fn has_arg(arg: &str) -> bool {
+    Args::from_env().has(arg)
+}
+
+pub fn arg_value(arg: &str) -> Option<String> {
+    Args::from_env().value(arg).map(String::from)
+}
+
+// Exits if the value can't be parsed. Only for main; days use Args::parsed.
+pub fn parsed_arg<T: FromStr>(arg: &str) -> Option<T> {
+    Args::from_env().parsed(arg).unwrap_or_else(|e| {
+        eprintln!("{}", e);
+        process::exit(1);
+    })
+}
+
+// FNV-1a hash of some data as hex. Not cryptographic, but stable across builds
+// and platforms, so it's fine for telling inputs apart.
+pub fn hash_hex(data: &[u8]) -> String {
+    let hash = data.iter().fold(0xcbf29ce484222325u64, |hash, byte| {
+        (hash ^ *byte as u64).wrapping_mul(0x100000001b3)
+    });
+    format!("{:016x}", hash)
 }
 
 pub async fn get_aoc_input(day: u16) -> String {
     if let Ok(input) = read_aoc_input_file(day) {
         if !input.is_empty() {
-            println!("Found local input data!");
+            eprintln!("Found local input data!");
             return input;
         }
     }
-    println!("Fetching data from remote instead...");
+    eprintln!("Fetching data from remote instead...");
 
     let result = get_aoc_input_from_remote(day).await;
 
     if !result.is_empty() {
-        println!("Writing input data...");
+        eprintln!("Writing input data...");
         write_aoc_input_file(day, &result);
-        return result;
+        result
     } else {
-        println!("Input data from remote is empty!");
+        eprintln!("Input data from remote is empty!");
         process::exit(1);
     }
 }
 
 async fn get_aoc_input_from_remote(day: u16) -> String {
-    let session_cookie = read_file("session_cookie.txt", "Could not read session cookie");
+    get_from_remote(&format!("/{}/day/{}/input", AOC_YEAR, day))
+        .await
+        .unwrap_or_else(|e| {
+            eprintln!("{}", e);
+            process::exit(1);
+        })
+}
+
+// Fetches a page from the Advent of Code site using the session cookie.
+async fn get_from_remote(path: &str) -> Result<String, String> {
+    let session_cookie = fs::read_to_string("session_cookie.txt")
+        .map_err(|e| format!("Could not read session cookie: {}", e))?;
+    if session_cookie.trim().is_empty() {
+        return Err("Could not read session cookie: File is empty.".to_string());
+    }
 
     let client = reqwest::Client::new();
-    let advent_url = format!("https://adventofcode.com/{}/day/{}/input", AOC_YEAR, day);
+    let advent_url = format!("https://adventofcode.com{}", path);
     let result = client
         .get(advent_url)
         .header(COOKIE, format!("session={}", session_cookie))
         .send()
         .await
-        .expect("Remote fetch failed");
+        .map_err(|e| format!("Remote fetch failed: {}", e))?;
 
     let status = result.status();
-    if status.is_success() {
-        return result.text().await.expect("Could not parse response text.");
-    } else {
-        println!("Data fetch unsuccessful! Code: {}.", status.as_u16());
-        process::exit(1);
+    if !status.is_success() {
+        return Err(format!(
+            "Data fetch unsuccessful! Code: {}.",
+            status.as_u16()
+        ));
     }
+    result
+        .text()
+        .await
+        .map_err(|e| format!("Could not parse response text: {}", e))
+}
+
+// Advent of Code asks that private leaderboards are fetched at most once every
+// 15 minutes, so we cache the JSON and only refresh it once it's that old.
+const LEADERBOARD_REFRESH_SECS: u64 = 15 * 60;
+
+// Leaderboard ids are numbers. Anything else could reach outside the input
+// directory or change the URL, so it's rejected before fetching or caching.
+fn is_leaderboard_id(id: &str) -> bool {
+    !id.is_empty() && id.bytes().all(|b| b.is_ascii_digit())
+}
+
+pub async fn get_leaderboard_json(year: u16, id: &str) -> Result<String, String> {
+    if !is_leaderboard_id(id) {
+        return Err(format!(
+            "'{}' is not a leaderboard id, which is all digits",
+            id
+        ));
+    }
+    let filename = format!("input/{}leaderboard{}.json", year, id);
+
+    let cache_age = fs::metadata(&filename)
+        .and_then(|metadata| metadata.modified())
+        .ok()
+        .and_then(|modified| SystemTime::now().duration_since(modified).ok());
+    if let Some(age) = cache_age {
+        if age.as_secs() < LEADERBOARD_REFRESH_SECS {
+            if let Ok(cached) = fs::read_to_string(&filename) {
+                eprintln!(
+                    "Using cached leaderboard ({}s old, refreshes after {}s).",
+                    age.as_secs(),
+                    LEADERBOARD_REFRESH_SECS
+                );
+                return Ok(cached);
+            }
+        }
+    }
+
+    eprintln!("Fetching leaderboard from remote...");
+    let result =
+        get_from_remote(&format!("/{}/leaderboard/private/view/{}.json", year, id)).await?;
+    fs::create_dir_all("input/")
+        .and_then(|_| fs::write(&filename, &result))
+        .map_err(|e| format!("Could not write {}: {}", filename, e))?;
+    Ok(result)
+}
+
+// Unix timestamp of when a puzzle unlocks: midnight US Eastern time (UTC-5)
+// on the given day of December.
+pub fn unlock_timestamp(year: u16, day: u16) -> i64 {
+    days_from_civil(year as i64, 12, day as i64) * 86400 + 5 * 3600
+}
+
+// Days since 1970-01-01 for a date in the proleptic Gregorian calendar.
+// See http://howardhinnant.github.io/date_algorithms.html#days_from_civil
+fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let month_index = (month + 9) % 12;
+    let day_of_year = (153 * month_index + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146097 + day_of_era - 719468
 }
 
 fn get_aoc_input_filename(day: u16) -> String {
     format!("input/{}day{:02}.txt", AOC_YEAR, day)
 }
 
-fn write_aoc_input_file(day: u16, contents: &String) {
+fn write_aoc_input_file(day: u16, contents: &str) {
     fs::create_dir_all("input/").expect("Could not create input directory");
-    fs::write(get_aoc_input_filename(day), &contents).expect("Could not write input file");
+    fs::write(get_aoc_input_filename(day), contents).expect("Could not write input file");
 }
 
 fn read_aoc_input_file(day: u16) -> Result<String, std::io::Error> {
     fs::read_to_string(get_aoc_input_filename(day))
 }
 
+fn get_answer_filename(day: u16, part_two: bool) -> String {
+    format!(
+        "input/{}day{:02}p{}_answer.txt",
+        AOC_YEAR,
+        day,
+        if part_two { 2 } else { 1 }
+    )
+}
+
+// Returns the known correct answer for the day and part, if one was saved.
+pub fn read_stored_answer(day: u16, part_two: bool) -> Option<String> {
+    match fs::read_to_string(get_answer_filename(day, part_two)) {
+        Ok(answer) if !answer.trim().is_empty() => Some(answer),
+        _ => None,
+    }
+}
+
+pub fn write_stored_answer(day: u16, part_two: bool, answer: &str) {
+    fs::create_dir_all("input/").expect("Could not create input directory");
+    fs::write(get_answer_filename(day, part_two), answer).expect("Could not write answer file");
+}
+
+#[derive(Debug, PartialEq)]
+pub enum SubmissionResult {
+    Correct,
+    // Includes any hint the site gave, like "your answer is too high."
+    Incorrect(Option<String>),
+    // The site rate-limits wrong answers. Includes the message with the wait time.
+    TooSoon(String),
+    // Usually means this part was already solved.
+    WrongLevel,
+    Unknown(String),
+}
+
+// Posts an answer to Advent of Code and interprets the response page.
+pub async fn submit_answer(day: u16, part_two: bool, answer: &Answer) -> SubmissionResult {
+    let submission = match answer.submission() {
+        Some(submission) => submission,
+        None => {
+            eprintln!("Answer cannot be submitted: {}", answer);
+            process::exit(1);
+        }
+    };
+    let session_cookie = read_file("session_cookie.txt", "Could not read session cookie");
+    let level = if part_two { "2" } else { "1" };
+
+    let client = reqwest::Client::new();
+    let advent_url = format!("https://adventofcode.com/{}/day/{}/answer", AOC_YEAR, day);
+    let result = client
+        .post(advent_url)
+        .header(COOKIE, format!("session={}", session_cookie))
+        .form(&[("level", level), ("answer", submission.as_str())])
+        .send()
+        .await
+        .expect("Remote submission failed");
+
+    let status = result.status();
+    if !status.is_success() {
+        eprintln!("Submission unsuccessful! Code: {}.", status.as_u16());
+        process::exit(1);
+    }
+    let page = result.text().await.expect("Could not parse response text.");
+    let submission_result = parse_submission_response(&page);
+
+    if submission_result == SubmissionResult::Correct {
+        write_stored_answer(day, part_two, &submission);
+    }
+    submission_result
+}
+
+fn parse_submission_response(page: &str) -> SubmissionResult {
+    // The interesting part of the page is the first <article>.
+    let message = match (page.find("<article>"), page.find("</article>")) {
+        (Some(start), Some(end)) if start < end => &page[start + 9..end],
+        _ => page,
+    };
+
+    if message.contains("That's the right answer") {
+        SubmissionResult::Correct
+    } else if message.contains("That's not the right answer") {
+        let hint = ["too high", "too low"]
+            .iter()
+            .find(|hint| message.contains(*hint))
+            .map(|hint| hint.to_string());
+        SubmissionResult::Incorrect(hint)
+    } else if message.contains("You gave an answer too recently") {
+        SubmissionResult::TooSoon(strip_tags(message))
+    } else if message.contains("You don't seem to be solving the right level") {
+        SubmissionResult::WrongLevel
+    } else {
+        SubmissionResult::Unknown(strip_tags(message))
+    }
+}
+
+// Very rough conversion from an HTML snippet to text, good enough for messages.
+fn strip_tags(html: &str) -> String {
+    let mut text = String::new();
+    let mut in_tag = false;
+    for c in html.chars() {
+        match c {
+            '<' => in_tag = true,
+            '>' => in_tag = false,
+            c if !in_tag => text.push(c),
+            _ => (),
+        }
+    }
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
 fn read_file(path: &str, err_msg: &str) -> String {
     match fs::read_to_string(path) {
         Ok(file_contents) => {
             if file_contents.trim().is_empty() {
-                println!("{}: File is empty.", err_msg);
+                eprintln!("{}: File is empty.", err_msg);
                 process::exit(1);
             }
-            return file_contents;
+            file_contents
         }
         Err(e) => {
             eprintln!("{}: {}", err_msg, e);
@@ -81,3 +341,47 @@ fn read_file(path: &str, err_msg: &str) -> String {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_unlock_timestamp() {
+        // 2022-12-01T05:00:00Z
+        assert_eq!(unlock_timestamp(2022, 1), 1669870800);
+        // 2015-12-25T05:00:00Z
+        assert_eq!(unlock_timestamp(2015, 25), 1451019600);
+    }
+
+    #[tokio::test]
+    async fn test_leaderboard_id() {
+        assert!(is_leaderboard_id("123456"));
+        for id in ["", "12a", "../../etc/passwd", "1?x=2", "１２", "-1"] {
+            assert!(!is_leaderboard_id(id), "{}", id);
+            // Rejected before anything is fetched or written.
+            assert!(get_leaderboard_json(2022, id).await.is_err());
+        }
+    }
+
+    #[test]
+    fn test_parse_submission_response() {
+        let page = "<main><article><p>That's the right answer! You are <em>one gold star</em> closer.</p></article></main>";
+        assert_eq!(parse_submission_response(page), SubmissionResult::Correct);
+
+        let page =
+            "<article><p>That's not the right answer; your answer is too high.</p></article>";
+        assert_eq!(
+            parse_submission_response(page),
+            SubmissionResult::Incorrect(Some("too high".to_string()))
+        );
+
+        let page = "<article><p>You gave an answer too recently; you have <b>42s</b> left to wait.</p></article>";
+        assert_eq!(
+            parse_submission_response(page),
+            SubmissionResult::TooSoon(
+                "You gave an answer too recently; you have 42s left to wait.".to_string()
+            )
+        );
+    }
+}
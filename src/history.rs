@@ -0,0 +1,327 @@
+// A local log of every run and submission, kept as one JSON object per line in
+// input/history.jsonl so it can be appended to cheaply and read by other tools.
+use crate::helpers;
+use crate::parsing::{ParseError, ParseResult};
+use serde_json::{json, Value};
+use std::collections::BTreeMap;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+const HISTORY_FILE: &str = "input/history.jsonl";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EntryKind {
+    Run,
+    Submission,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Entry {
+    pub kind: EntryKind,
+    // Unix timestamp in seconds.
+    pub timestamp: i64,
+    pub year: u16,
+    pub day: u16,
+    pub part: u8,
+    pub answer: Value,
+    // The run status (e.g. "correct" or "unsolved"), or the submission result.
+    pub status: String,
+    // None when we don't know, e.g. a run with no stored answer to compare to.
+    pub correct: Option<bool>,
+    pub time_ms: Option<f64>,
+    pub source_hash: Option<String>,
+    pub input_hash: Option<String>,
+}
+
+impl Entry {
+    fn to_json(&self) -> Value {
+        json!({
+            "kind": match self.kind {
+                EntryKind::Run => "run",
+                EntryKind::Submission => "submission",
+            },
+            "timestamp": self.timestamp,
+            "year": self.year,
+            "day": self.day,
+            "part": self.part,
+            "answer": self.answer,
+            "status": self.status,
+            "correct": self.correct,
+            "time_ms": self.time_ms,
+            "source_hash": self.source_hash,
+            "input_hash": self.input_hash,
+        })
+    }
+
+    fn from_json(value: &Value, line: usize) -> ParseResult<Entry> {
+        let missing = |field: &str| ParseError::new(line, format!("missing '{}'", field));
+        let kind = match value["kind"].as_str() {
+            Some("run") => EntryKind::Run,
+            Some("submission") => EntryKind::Submission,
+            _ => return Err(missing("kind")),
+        };
+        let small_int = |field: &str| {
+            value[field]
+                .as_u64()
+                .and_then(|n| u16::try_from(n).ok())
+                .ok_or_else(|| missing(field))
+        };
+        let part = match small_int("part")? {
+            1 => 1,
+            2 => 2,
+            part => return Err(ParseError::new(line, format!("bad part {}", part))),
+        };
+
+        Ok(Entry {
+            kind,
+            timestamp: value["timestamp"]
+                .as_i64()
+                .ok_or_else(|| missing("timestamp"))?,
+            year: small_int("year")?,
+            day: small_int("day")?,
+            part,
+            answer: value["answer"].clone(),
+            status: value["status"].as_str().unwrap_or("").to_string(),
+            correct: value["correct"].as_bool(),
+            time_ms: value["time_ms"].as_f64(),
+            source_hash: value["source_hash"].as_str().map(String::from),
+            input_hash: value["input_hash"].as_str().map(String::from),
+        })
+    }
+}
+
+pub fn now() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs() as i64)
+        .unwrap_or(0)
+}
+
+// Hashes the source of a day's solution, so runtimes can be compared across
+// refactors. Days can be a single file or a directory of modules.
+pub fn source_hash(year: u16, day: u16) -> Option<String> {
+    let file = format!("src/y{}/day{}.rs", year, day);
+    if let Ok(source) = fs::read(&file) {
+        return Some(helpers::hash_hex(&source));
+    }
+
+    let dir = format!("src/y{}/day{}", year, day);
+    let mut paths: Vec<_> = fs::read_dir(dir)
+        .ok()?
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .collect();
+    paths.sort();
+    let mut source = Vec::new();
+    for path in paths {
+        source.extend(fs::read(path).ok()?);
+    }
+    Some(helpers::hash_hex(&source))
+}
+
+pub fn record(entry: &Entry) {
+    let result = fs::create_dir_all("input/").and_then(|_| {
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(HISTORY_FILE)?;
+        writeln!(file, "{}", entry.to_json())
+    });
+    // Losing a history entry isn't worth failing the run over.
+    if let Err(e) = result {
+        eprintln!("Could not write to {}: {}", HISTORY_FILE, e);
+    }
+}
+
+// Reads the whole history. Malformed lines are reported and skipped, so one bad
+// line doesn't lose everything else.
+pub fn load() -> Vec<Entry> {
+    let contents = fs::read_to_string(HISTORY_FILE).unwrap_or_default();
+    let (entries, errors) = parse_history(&contents);
+    for e in errors {
+        eprintln!("Skipping bad history entry: {}", e);
+    }
+    entries
+}
+
+pub fn parse_history(contents: &str) -> (Vec<Entry>, Vec<ParseError>) {
+    let mut entries = Vec::new();
+    let mut errors = Vec::new();
+    for (i, line) in contents.lines().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let parsed = serde_json::from_str::<Value>(line)
+            .map_err(|e| ParseError::new(i + 1, e.to_string()))
+            .and_then(|value| Entry::from_json(&value, i + 1));
+        match parsed {
+            Ok(entry) => entries.push(entry),
+            Err(e) => errors.push(e),
+        }
+    }
+    (entries, errors)
+}
+
+// Summarizes the history for each (year, day, part), optionally for one day.
+pub fn render_stats(entries: &[Entry], only_day: Option<u16>) -> String {
+    let mut by_puzzle: BTreeMap<(u16, u16, u8), Vec<&Entry>> = BTreeMap::new();
+    for entry in entries {
+        if only_day.is_some_and(|day| day != entry.day) {
+            continue;
+        }
+        by_puzzle
+            .entry((entry.year, entry.day, entry.part))
+            .or_default()
+            .push(entry);
+    }
+
+    if by_puzzle.is_empty() {
+        return "No history yet. Run a day to start recording.\n".to_string();
+    }
+
+    let mut out = String::new();
+    for ((year, day, part), mut puzzle_entries) in by_puzzle {
+        puzzle_entries.sort_by_key(|e| e.timestamp);
+        out.push_str(&format!("{} day {} part {}\n", year, day, part));
+
+        let first_correct = puzzle_entries
+            .iter()
+            .find(|e| e.kind == EntryKind::Submission && e.correct == Some(true))
+            .or_else(|| puzzle_entries.iter().find(|e| e.correct == Some(true)));
+        let wrong_attempts = puzzle_entries
+            .iter()
+            .filter(|e| {
+                e.kind == EntryKind::Submission
+                    && e.correct == Some(false)
+                    && first_correct.is_none_or(|c| e.timestamp <= c.timestamp)
+            })
+            .count();
+
+        match first_correct {
+            Some(correct) => {
+                // Saturating, since a hand-edited timestamp could be anything.
+                let since_unlock = correct
+                    .timestamp
+                    .saturating_sub(helpers::unlock_timestamp(year, day));
+                out.push_str(&format!(
+                    "  First correct answer {} after unlock, {} wrong attempt{}.\n",
+                    format_duration(since_unlock),
+                    wrong_attempts,
+                    if wrong_attempts == 1 { "" } else { "s" }
+                ));
+            }
+            None => out.push_str(&format!(
+                "  Not solved yet, {} wrong attempt{}.\n",
+                wrong_attempts,
+                if wrong_attempts == 1 { "" } else { "s" }
+            )),
+        }
+
+        // Group runtimes by source version, in the order each version first ran.
+        let mut versions: Vec<(String, Vec<f64>)> = Vec::new();
+        for entry in puzzle_entries.iter().filter(|e| e.kind == EntryKind::Run) {
+            let (Some(time_ms), hash) = (entry.time_ms, &entry.source_hash) else {
+                continue;
+            };
+            let hash = hash.clone().unwrap_or_else(|| "unknown".to_string());
+            match versions.iter_mut().find(|(h, _)| *h == hash) {
+                Some((_, times)) => times.push(time_ms),
+                None => versions.push((hash, vec![time_ms])),
+            }
+        }
+        if !versions.is_empty() {
+            out.push_str("  Runtimes by source version:\n");
+        }
+        for (hash, times) in versions {
+            let min = times.iter().cloned().fold(f64::INFINITY, f64::min);
+            let mean = times.iter().sum::<f64>() / times.len() as f64;
+            out.push_str(&format!(
+                "    {:<16}  {:>4} run{}  min {:>10.3}ms  mean {:>10.3}ms\n",
+                hash,
+                times.len(),
+                if times.len() == 1 { " " } else { "s" },
+                min,
+                mean
+            ));
+        }
+    }
+    out
+}
+
+fn format_duration(secs: i64) -> String {
+    let sign = if secs < 0 { "-" } else { "" };
+    let secs = secs.unsigned_abs();
+    if secs >= 86400 {
+        format!(
+            "{}{}d {:02}:{:02}:{:02}",
+            sign,
+            secs / 86400,
+            secs / 3600 % 24,
+            secs / 60 % 60,
+            secs % 60
+        )
+    } else {
+        format!(
+            "{}{:02}:{:02}:{:02}",
+            sign,
+            secs / 3600,
+            secs / 60 % 60,
+            secs % 60
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(kind: EntryKind, timestamp: i64, correct: Option<bool>, source: &str) -> Entry {
+        Entry {
+            kind,
+            timestamp,
+            year: 2022,
+            day: 1,
+            part: 1,
+            answer: json!(24000),
+            status: String::new(),
+            correct,
+            time_ms: Some(2.0),
+            source_hash: Some(source.to_string()),
+            input_hash: None,
+        }
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let original = entry(EntryKind::Submission, 1669870900, Some(false), "abc");
+        let line = format!("{}\nnot json\n{{\"kind\": \"run\"}}\n", original.to_json());
+        // Only parts 1 and 2 exist, and 257 mustn't wrap round to 1.
+        let bad_part = original
+            .to_json()
+            .to_string()
+            .replace("\"part\":1", "\"part\":257");
+        let (entries, errors) = parse_history(&line);
+        assert_eq!(entries, vec![original]);
+        assert_eq!(errors.len(), 2);
+        assert_eq!(errors[1].line, 3);
+
+        assert_eq!(parse_history(&bad_part).1.len(), 1);
+    }
+
+    #[test]
+    fn test_render_stats() {
+        let unlock = helpers::unlock_timestamp(2022, 1);
+        let entries = vec![
+            entry(EntryKind::Run, unlock + 60, None, "aaaa"),
+            entry(EntryKind::Submission, unlock + 61, Some(false), "aaaa"),
+            entry(EntryKind::Run, unlock + 90, None, "bbbb"),
+            entry(EntryKind::Submission, unlock + 95, Some(true), "bbbb"),
+            entry(EntryKind::Run, unlock + 200, Some(true), "bbbb"),
+        ];
+        let stats = render_stats(&entries, None);
+        assert!(stats.contains("First correct answer 00:01:35 after unlock, 1 wrong attempt."));
+        assert!(stats.contains("    aaaa                 1 run "));
+        assert!(stats.contains("    bbbb                 2 runs"));
+        assert!(render_stats(&entries, Some(2)).starts_with("No history yet"));
+    }
+}
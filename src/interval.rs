@@ -0,0 +1,331 @@
+// Inclusive integer intervals like "2-4" (which covers 2, 3 and 4), and sets
+// of them. Sets keep their intervals sorted, and merge any that overlap or
+// touch, so every set has exactly one representation.
+use std::fmt;
+
+// The integer types intervals can be made of.
+pub trait Integer: Copy + Ord + fmt::Debug {
+    fn succ(self) -> Option<Self>;
+    fn pred(self) -> Option<Self>;
+    // hi - lo, which always fits in a u128 when lo <= hi.
+    fn distance(lo: Self, hi: Self) -> u128;
+}
+
+macro_rules! impl_integer {
+    ($($t:ty),*) => {
+        $(
+            impl Integer for $t {
+                fn succ(self) -> Option<Self> {
+                    self.checked_add(1)
+                }
+
+                fn pred(self) -> Option<Self> {
+                    self.checked_sub(1)
+                }
+
+                fn distance(lo: Self, hi: Self) -> u128 {
+                    // Wrapping is fine: the true difference is below 2^128, so
+                    // it comes out right modulo 2^128.
+                    (hi as i128).wrapping_sub(lo as i128) as u128
+                }
+            }
+        )*
+    };
+}
+
+impl_integer!(i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Interval<T> {
+    pub start: T,
+    pub end: T,
+}
+
+impl<T: Integer> Interval<T> {
+    // Returns None if the interval would be backwards.
+    pub fn new(start: T, end: T) -> Option<Interval<T>> {
+        (start <= end).then_some(Interval { start, end })
+    }
+
+    // The number of values covered. Saturates for intervals spanning the whole
+    // of u128 or i128.
+    pub fn len(&self) -> u128 {
+        T::distance(self.start, self.end).saturating_add(1)
+    }
+
+    // True if the other interval is entirely within this one.
+    pub fn contains_interval(&self, other: &Interval<T>) -> bool {
+        self.start <= other.start && other.end <= self.end
+    }
+
+    // True if the intervals have at least one value in common.
+    pub fn overlaps(&self, other: &Interval<T>) -> bool {
+        self.start <= other.end && other.start <= self.end
+    }
+
+    // True if the intervals overlap or sit right next to each other, so they
+    // could be combined into one.
+    fn touches(&self, other: &Interval<T>) -> bool {
+        self.overlaps(other)
+            || self.end.succ() == Some(other.start)
+            || other.end.succ() == Some(self.start)
+    }
+}
+
+// Not needed by any puzzle yet, but kept for later ones with ranges in them.
+#[allow(dead_code)]
+impl<T: Integer> Interval<T> {
+    pub fn single(value: T) -> Interval<T> {
+        Interval {
+            start: value,
+            end: value,
+        }
+    }
+
+    pub fn contains(&self, value: T) -> bool {
+        self.start <= value && value <= self.end
+    }
+
+    pub fn intersection(&self, other: &Interval<T>) -> Option<Interval<T>> {
+        Interval::new(self.start.max(other.start), self.end.min(other.end))
+    }
+}
+
+impl<T: fmt::Display> fmt::Display for Interval<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}-{}", self.start, self.end)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct IntervalSet<T> {
+    // Sorted, and no two intervals overlap or touch.
+    intervals: Vec<Interval<T>>,
+}
+
+impl<T: Integer> FromIterator<Interval<T>> for IntervalSet<T> {
+    fn from_iter<I: IntoIterator<Item = Interval<T>>>(iter: I) -> IntervalSet<T> {
+        let mut intervals: Vec<Interval<T>> = iter.into_iter().collect();
+        intervals.sort_unstable();
+        IntervalSet {
+            intervals: merge_sorted(intervals),
+        }
+    }
+}
+
+// Combines overlapping and touching intervals from a sorted list.
+fn merge_sorted<T: Integer>(sorted: Vec<Interval<T>>) -> Vec<Interval<T>> {
+    let mut merged: Vec<Interval<T>> = Vec::with_capacity(sorted.len());
+    for interval in sorted {
+        match merged.last_mut() {
+            Some(last) if last.touches(&interval) => last.end = last.end.max(interval.end),
+            _ => merged.push(interval),
+        }
+    }
+    merged
+}
+
+impl<T: Integer> IntervalSet<T> {
+    pub fn new() -> IntervalSet<T> {
+        IntervalSet {
+            intervals: Vec::new(),
+        }
+    }
+
+    // Adds an interval, merging it with any it overlaps or touches.
+    pub fn insert(&mut self, interval: Interval<T>) {
+        let first = self.intervals.partition_point(|existing| {
+            existing.end < interval.start && !existing.touches(&interval)
+        });
+        let mut merged = interval;
+        let mut last = first;
+        while last < self.intervals.len() && self.intervals[last].touches(&merged) {
+            merged.start = merged.start.min(self.intervals[last].start);
+            merged.end = merged.end.max(self.intervals[last].end);
+            last += 1;
+        }
+        self.intervals.splice(first..last, [merged]);
+    }
+
+    // The total number of values covered. Saturates rather than overflowing.
+    pub fn len(&self) -> u128 {
+        self.intervals.iter().fold(0u128, |total, interval| {
+            total.saturating_add(interval.len())
+        })
+    }
+
+    // The values missing between the lowest and highest values in the set.
+    pub fn gaps(&self) -> IntervalSet<T> {
+        let intervals = self
+            .intervals
+            .windows(2)
+            // Intervals in a set never touch, so there's always a gap.
+            .map(|pair| Interval {
+                start: pair[0].end.succ().unwrap(),
+                end: pair[1].start.pred().unwrap(),
+            })
+            .collect();
+        IntervalSet { intervals }
+    }
+}
+
+// Not needed by any puzzle yet either.
+#[allow(dead_code)]
+impl<T: Integer> IntervalSet<T> {
+    pub fn intervals(&self) -> &[Interval<T>] {
+        &self.intervals
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.intervals.is_empty()
+    }
+
+    pub fn contains(&self, value: T) -> bool {
+        let i = self
+            .intervals
+            .partition_point(|interval| interval.end < value);
+        self.intervals
+            .get(i)
+            .is_some_and(|interval| interval.contains(value))
+    }
+
+    pub fn union(&self, other: &IntervalSet<T>) -> IntervalSet<T> {
+        self.intervals
+            .iter()
+            .chain(&other.intervals)
+            .copied()
+            .collect()
+    }
+
+    pub fn intersection(&self, other: &IntervalSet<T>) -> IntervalSet<T> {
+        let mut result = Vec::new();
+        let (mut i, mut j) = (0, 0);
+        while i < self.intervals.len() && j < other.intervals.len() {
+            let (a, b) = (self.intervals[i], other.intervals[j]);
+            if let Some(common) = a.intersection(&b) {
+                result.push(common);
+            }
+            // Whichever ends first can't overlap anything else.
+            if a.end < b.end {
+                i += 1;
+            } else {
+                j += 1;
+            }
+        }
+        // Pieces of disjoint intervals can't touch, so there's nothing to merge.
+        IntervalSet { intervals: result }
+    }
+
+    // Everything in this set which isn't in the other.
+    pub fn difference(&self, other: &IntervalSet<T>) -> IntervalSet<T> {
+        let mut result = Vec::new();
+        let mut j = 0;
+        for interval in &self.intervals {
+            let mut remaining = Some(*interval);
+            // Skip the other's intervals which end before this one starts.
+            while j < other.intervals.len() && other.intervals[j].end < interval.start {
+                j += 1;
+            }
+            let mut k = j;
+            while let (Some(rest), Some(cut)) = (remaining, other.intervals.get(k)) {
+                if cut.start > rest.end {
+                    break;
+                }
+                if cut.start > rest.start {
+                    // Keep the piece before the cut.
+                    result.push(Interval {
+                        start: rest.start,
+                        end: cut.start.pred().unwrap(),
+                    });
+                }
+                remaining = cut
+                    .end
+                    .succ()
+                    .and_then(|start| Interval::new(start, rest.end));
+                k += 1;
+            }
+            if let Some(rest) = remaining {
+                result.push(rest);
+            }
+        }
+        IntervalSet { intervals: result }
+    }
+}
+
+impl<T: fmt::Display> fmt::Display for IntervalSet<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let parts: Vec<String> = self.intervals.iter().map(|i| i.to_string()).collect();
+        write!(f, "{{{}}}", parts.join(", "))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn set(intervals: &[(i32, i32)]) -> IntervalSet<i32> {
+        intervals
+            .iter()
+            .map(|(start, end)| Interval::new(*start, *end).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn test_interval() {
+        let a = Interval::new(2u32, 4).unwrap();
+        let b = Interval::new(4u32, 8).unwrap();
+        assert!(Interval::new(4u32, 2).is_none());
+        assert_eq!(a.len(), 3);
+        assert!(a.overlaps(&b) && b.overlaps(&a));
+        assert!(!a.contains_interval(&b));
+        assert!(b.contains_interval(&Interval::single(6)));
+        assert_eq!(a.intersection(&b), Some(Interval::single(4)));
+        assert_eq!(Interval::new(i64::MIN, i64::MAX).unwrap().len(), 1 << 64);
+        assert_eq!(Interval::new(0u128, u128::MAX).unwrap().len(), u128::MAX);
+    }
+
+    #[test]
+    fn test_merging() {
+        // Overlapping and touching intervals are combined.
+        let merged = set(&[(5, 7), (1, 2), (3, 3), (10, 12), (11, 20)]);
+        assert_eq!(merged, set(&[(1, 3), (5, 7), (10, 20)]));
+        assert_eq!(merged.len(), 3 + 3 + 11);
+        assert_eq!(merged.to_string(), "{1-3, 5-7, 10-20}");
+
+        let mut inserted = merged.clone();
+        inserted.insert(Interval::new(4, 9).unwrap());
+        assert_eq!(inserted, set(&[(1, 20)]));
+        let mut inserted = merged.clone();
+        inserted.insert(Interval::single(-5));
+        inserted.insert(Interval::new(22, 30).unwrap());
+        assert_eq!(
+            inserted,
+            set(&[(-5, -5), (1, 3), (5, 7), (10, 20), (22, 30)])
+        );
+        assert!(inserted.contains(25) && !inserted.contains(21));
+    }
+
+    #[test]
+    fn test_set_operations() {
+        let a = set(&[(1, 10), (20, 30)]);
+        let b = set(&[(5, 25), (28, 40)]);
+        assert_eq!(a.union(&b), set(&[(1, 40)]));
+        assert_eq!(a.intersection(&b), set(&[(5, 10), (20, 25), (28, 30)]));
+        assert_eq!(a.difference(&b), set(&[(1, 4), (26, 27)]));
+        assert_eq!(b.difference(&a), set(&[(11, 19), (31, 40)]));
+        assert_eq!(a.gaps(), set(&[(11, 19)]));
+        assert!(a.difference(&a).is_empty());
+        assert!(set(&[(1, 5)]).gaps().is_empty());
+
+        // Differences right at the edges of the type.
+        let all: IntervalSet<u8> = [Interval::new(0, 255).unwrap()].into_iter().collect();
+        let ends: IntervalSet<u8> = [Interval::single(0), Interval::single(255)]
+            .into_iter()
+            .collect();
+        assert_eq!(
+            all.difference(&ends).intervals(),
+            &[Interval::new(1, 254).unwrap()]
+        );
+        assert_eq!(ends.gaps(), all.difference(&ends));
+    }
+}
@@ -0,0 +1,250 @@
+// Renders a private leaderboard from the JSON API, e.g.
+// https://adventofcode.com/2022/leaderboard/private/view/12345.json
+use crate::helpers;
+use crate::parsing::{ParseError, ParseResult};
+use serde_json::Value;
+
+pub struct Leaderboard {
+    pub year: u16,
+    pub members: Vec<Member>,
+}
+
+pub struct Member {
+    pub name: String,
+    pub local_score: u64,
+    pub stars: u64,
+    // Timestamps of when each star was earned, indexed by day - 1. The tuple is
+    // (part one, part two).
+    pub star_times: [(Option<i64>, Option<i64>); 25],
+}
+
+impl Member {
+    fn stars_on_day(&self, day: usize) -> usize {
+        let (one, two) = self.star_times[day - 1];
+        one.is_some() as usize + two.is_some() as usize
+    }
+}
+
+// Parses the leaderboard JSON. Members are sorted by their standing.
+pub fn parse_leaderboard(json: &str) -> ParseResult<Leaderboard> {
+    let root: Value =
+        serde_json::from_str(json).map_err(|e| ParseError::new(e.line(), e.to_string()))?;
+
+    let year = match &root["event"] {
+        Value::String(event) => event.parse::<u16>().ok(),
+        _ => None,
+    }
+    .ok_or_else(|| ParseError::new(1, "missing or invalid 'event'"))?;
+
+    let members_json = root["members"]
+        .as_object()
+        .ok_or_else(|| ParseError::new(1, "missing 'members'"))?;
+
+    let mut members = Vec::new();
+    for (id, member) in members_json {
+        let name = match member["name"].as_str() {
+            Some(name) => name.to_string(),
+            None => format!("(anonymous user #{})", id),
+        };
+
+        let mut star_times = [(None, None); 25];
+        if let Some(days) = member["completion_day_level"].as_object() {
+            for (day, parts) in days {
+                let day = match day.parse::<usize>() {
+                    Ok(day) if (1..=25).contains(&day) => day,
+                    _ => return Err(ParseError::new(1, format!("invalid day '{}'", day))),
+                };
+                star_times[day - 1] = (
+                    parts["1"]["get_star_ts"].as_i64(),
+                    parts["2"]["get_star_ts"].as_i64(),
+                );
+            }
+        }
+
+        members.push(Member {
+            name,
+            local_score: member["local_score"].as_u64().unwrap_or(0),
+            stars: member["stars"].as_u64().unwrap_or(0),
+            star_times,
+        });
+    }
+
+    members.sort_by(|a, b| {
+        b.local_score
+            .cmp(&a.local_score)
+            .then(b.stars.cmp(&a.stars))
+            .then(a.name.cmp(&b.name))
+    });
+
+    Ok(Leaderboard { year, members })
+}
+
+pub fn render(leaderboard: &Leaderboard) -> String {
+    let mut out = String::new();
+    let name_width = leaderboard
+        .members
+        .iter()
+        .map(|m| m.name.chars().count())
+        .max()
+        .unwrap_or(0)
+        .max(4);
+
+    // Only show days which have unlocked (i.e. someone has a star).
+    let last_day = (1..=25)
+        .rev()
+        .find(|day| leaderboard.members.iter().any(|m| m.stars_on_day(*day) > 0))
+        .unwrap_or(0);
+
+    out.push_str(&format!(
+        "Advent of Code {} standings\n\n",
+        leaderboard.year
+    ));
+    out.push_str(&format!(
+        "{:>4}  {:>5}  {:>5}  {}\n",
+        "Rank", "Score", "Stars", "Name"
+    ));
+    for (i, member) in leaderboard.members.iter().enumerate() {
+        out.push_str(&format!(
+            "{:>4}  {:>5}  {:>5}  {}\n",
+            i + 1,
+            member.local_score,
+            member.stars,
+            member.name
+        ));
+    }
+
+    // The star grid uses "*" for both stars, "+" for one, and "." for none.
+    out.push_str("\nStars by day (* = both, + = part one only)\n\n");
+    let tens: String = (1..=last_day)
+        .map(|day| {
+            if day >= 10 {
+                format!("{}", day / 10)
+            } else {
+                " ".to_string()
+            }
+        })
+        .collect();
+    let ones: String = (1..=last_day).map(|day| format!("{}", day % 10)).collect();
+    out.push_str(&format!(
+        "{:<name_width$}  {}\n",
+        "",
+        tens,
+        name_width = name_width
+    ));
+    out.push_str(&format!(
+        "{:<name_width$}  {}\n",
+        "",
+        ones,
+        name_width = name_width
+    ));
+    for member in &leaderboard.members {
+        let row: String = (1..=last_day)
+            .map(|day| match member.stars_on_day(day) {
+                2 => '*',
+                1 => '+',
+                _ => '.',
+            })
+            .collect();
+        out.push_str(&format!(
+            "{:<name_width$}  {}\n",
+            member.name,
+            row,
+            name_width = name_width
+        ));
+    }
+
+    // Solve times are measured from when the puzzle unlocked.
+    out.push_str("\nSolve times (from unlock, part one / part two)\n");
+    for member in &leaderboard.members {
+        if member.stars == 0 {
+            continue;
+        }
+        out.push_str(&format!("\n{}\n", member.name));
+        for day in 1..=last_day {
+            let (one, two) = member.star_times[day - 1];
+            if one.is_none() {
+                continue;
+            }
+            let unlock = helpers::unlock_timestamp(leaderboard.year, day as u16);
+            out.push_str(&format!(
+                "  Day {:>2}  {:>9}  {:>9}\n",
+                day,
+                format_solve_time(one.map(|t| t.saturating_sub(unlock))),
+                format_solve_time(two.map(|t| t.saturating_sub(unlock))),
+            ));
+        }
+    }
+
+    out
+}
+
+// Formats a duration in seconds like the site does: HH:MM:SS, or ">24h".
+fn format_solve_time(secs: Option<i64>) -> String {
+    match secs {
+        None => "-".to_string(),
+        Some(secs) if secs >= 24 * 3600 => ">24h".to_string(),
+        Some(secs) => {
+            let secs = secs.max(0);
+            format!("{:02}:{:02}:{:02}", secs / 3600, secs / 60 % 60, secs % 60)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = r#"{
+        "event": "2022",
+        "owner_id": 1,
+        "members": {
+            "1": {
+                "id": 1, "name": "Ada", "stars": 3, "local_score": 10, "global_score": 0,
+                "last_star_ts": 1669957200,
+                "completion_day_level": {
+                    "1": {
+                        "1": {"get_star_ts": 1669870860, "star_index": 0},
+                        "2": {"get_star_ts": 1669874400, "star_index": 1}
+                    },
+                    "2": {"1": {"get_star_ts": 1670043600, "star_index": 2}}
+                }
+            },
+            "2": {
+                "id": 2, "name": null, "stars": 4, "local_score": 12, "global_score": 0,
+                "last_star_ts": 0,
+                "completion_day_level": {
+                    "1": {
+                        "1": {"get_star_ts": 1669870805, "star_index": 3},
+                        "2": {"get_star_ts": 1669870810, "star_index": 4}
+                    },
+                    "2": {
+                        "1": {"get_star_ts": 1669957205, "star_index": 5},
+                        "2": {"get_star_ts": 1669957210, "star_index": 6}
+                    }
+                }
+            }
+        }
+    }"#;
+
+    #[test]
+    fn test_parse_leaderboard() {
+        let leaderboard = parse_leaderboard(EXAMPLE).unwrap();
+        assert_eq!(leaderboard.year, 2022);
+        assert_eq!(leaderboard.members[0].name, "(anonymous user #2)");
+        assert_eq!(leaderboard.members[1].name, "Ada");
+        assert_eq!(leaderboard.members[1].stars_on_day(2), 1);
+
+        assert!(parse_leaderboard("{").is_err());
+        assert!(parse_leaderboard(r#"{"event": "2022"}"#).is_err());
+    }
+
+    #[test]
+    fn test_render() {
+        let rendered = render(&parse_leaderboard(EXAMPLE).unwrap());
+        assert!(rendered.contains("   1     12      4  (anonymous user #2)"));
+        assert!(rendered.contains("Ada                  *+"));
+        // Ada took a minute for part one of day 1, and more than a day for day 2.
+        assert!(rendered.contains("  Day  1   00:01:00   01:00:00"));
+        assert!(rendered.contains("  Day  2       >24h          -"));
+    }
+}
@@ -1,41 +1,409 @@
+use answer::Answer;
+use helpers::SubmissionResult;
+use rng::Rng;
+use serde_json::json;
 use std::env;
+use std::fs;
+use std::panic;
+use std::path::Path;
 use std::process;
+use std::time::Instant;
+use y2022::generate::{self, Size};
+mod answer;
+mod fuzz;
 mod helpers;
+mod history;
+mod interval;
+mod leaderboard;
+mod ocr;
+mod parsing;
+mod ppm;
+mod rng;
 // Change next three lines for the new year :)
 pub static AOC_YEAR: u16 = 2022;
 mod y2022;
 
+// Everything other than the result itself goes to stderr, so stdout can be
+// piped into scripts. Use "--format json" to get a single JSON object instead
+// of the human-readable result.
 #[tokio::main]
 async fn main() {
     let args: Vec<String> = env::args().collect();
 
     if args.len() <= 1 {
-        println!("Please pass an argument for the day.");
+        eprintln!("Please pass an argument for the day.");
         process::exit(1);
     }
+    if args[1] == "leaderboard" {
+        show_leaderboard(args.get(2)).await;
+        return;
+    }
+    if args[1] == "stats" {
+        let day = args.get(2).and_then(|day| day.parse::<u16>().ok());
+        print!("{}", history::render_stats(&history::load(), day));
+        return;
+    }
+    if args[1] == "generate" {
+        generate_input(args.get(2));
+        return;
+    }
+    if args[1] == "bench" {
+        bench(args.get(2));
+        return;
+    }
+    if args[1] == "fuzz" {
+        run_fuzzer(args.get(2));
+        return;
+    }
     let day = args[1].parse::<u16>().expect("Could not parse day number");
-    println!("Running challenge for day: {}", day);
+    let json_output = match helpers::arg_value("--format").as_deref() {
+        None | Some("text") => false,
+        Some("json") => true,
+        Some(other) => {
+            eprintln!("Unknown format: {}. Use \"text\" or \"json\".", other);
+            process::exit(1);
+        }
+    };
+    eprintln!("Running challenge for day: {}", day);
 
     let input = helpers::get_aoc_input(day).await;
 
-    if has_arg("--show-input") {
-        println!("Input: {}", input);
+    if helpers::has_arg("--show-input") {
+        eprintln!("Input: {}", input);
     }
 
-    println!("Running exercise...\n");
+    eprintln!("Running exercise...\n");
     // Use "--p2" or "--part-two" to show we've moved on to the next part.
-    let is_part_two = has_arg("--part-two") || has_arg("--p2");
-    let result = y2022::run_day(day, &input, is_part_two);
+    let is_part_two = helpers::has_arg("--part-two") || helpers::has_arg("--p2");
+    let start = Instant::now();
+    let result = y2022::run_day(day, &input, is_part_two, &helpers::Args::from_env());
+    let time_ms = start.elapsed().as_secs_f64() * 1000.0;
 
-    if result.is_empty() {
-        println!("Result is empty!");
+    let mut report = json!({
+        "year": AOC_YEAR,
+        "day": day,
+        "part": if is_part_two { 2 } else { 1 },
+        "time_ms": time_ms,
+        "input_hash": helpers::hash_hex(input.as_bytes()),
+    });
+
+    // Every run is saved to the local history, unless "--no-history" is passed.
+    let part = if is_part_two { 2 } else { 1 };
+    let source_hash = history::source_hash(AOC_YEAR, day);
+    let record_history = |kind, status: &str, answer: &Answer, correct, time_ms| {
+        if helpers::has_arg("--no-history") {
+            return;
+        }
+        history::record(&history::Entry {
+            kind,
+            timestamp: history::now(),
+            year: AOC_YEAR,
+            day,
+            part,
+            answer: answer.to_json(),
+            status: status.to_string(),
+            correct,
+            time_ms,
+            source_hash: source_hash.clone(),
+            input_hash: Some(helpers::hash_hex(input.as_bytes())),
+        });
+    };
+
+    let result = match result {
+        Ok(result) => result,
+        Err(e) => {
+            let no_answer = Answer::Unsolved(e.to_string());
+            record_history(
+                history::EntryKind::Run,
+                "error",
+                &no_answer,
+                None,
+                Some(time_ms),
+            );
+            if json_output {
+                report["status"] = json!("error");
+                report["answer"] = json!(null);
+                report["error"] = json!(e.to_string());
+                println!("{}", report);
+            } else {
+                eprintln!("Could not parse input: {}", e);
+            }
+            process::exit(1);
+        }
+    };
+    report["answer"] = result.to_json();
+    report["answer_kind"] = json!(result.kind());
+
+    // Compare to the answer we know is right, if we have one.
+    let stored = helpers::read_stored_answer(day, is_part_two);
+    let status = match (&stored, result.is_solved()) {
+        (_, false) => "unsolved",
+        (Some(stored), true) if result.matches(stored) => "correct",
+        (Some(_), true) => "incorrect",
+        (None, true) => "solved",
+    };
+    report["status"] = json!(status);
+    let correct = match status {
+        "correct" => Some(true),
+        "incorrect" => Some(false),
+        _ => None,
+    };
+    record_history(
+        history::EntryKind::Run,
+        status,
+        &result,
+        correct,
+        Some(time_ms),
+    );
+
+    if !result.is_solved() {
+        if json_output {
+            println!("{}", report);
+        } else if let Answer::Unsolved(reason) = &result {
+            eprintln!("No solution found: {}", reason);
+        } else {
+            eprintln!("Result is empty!");
+        }
         process::exit(1);
     }
 
-    println!("\nResult: {}", result);
+    if !json_output {
+        println!("\nResult: {}", result);
+    }
+    eprintln!("Finished in {:.3}ms.", time_ms);
+
+    match (status, &stored) {
+        ("correct", _) => eprintln!("Matches the stored answer."),
+        ("incorrect", Some(stored)) => {
+            eprintln!("Does NOT match the stored answer: {}", stored.trim())
+        }
+        _ => (),
+    }
+
+    // Use "--submit" to send the answer to Advent of Code.
+    if helpers::has_arg("--submit") {
+        let submission = helpers::submit_answer(day, is_part_two, &result).await;
+        let submission_status = match &submission {
+            SubmissionResult::Correct => "correct",
+            SubmissionResult::Incorrect(_) => "incorrect",
+            SubmissionResult::TooSoon(_) => "too_soon",
+            SubmissionResult::WrongLevel => "wrong_level",
+            SubmissionResult::Unknown(_) => "unknown",
+        };
+        report["submission"] = json!(submission_status);
+        let correct = match &submission {
+            SubmissionResult::Correct => Some(true),
+            SubmissionResult::Incorrect(_) => Some(false),
+            _ => None,
+        };
+        record_history(
+            history::EntryKind::Submission,
+            submission_status,
+            &result,
+            correct,
+            None,
+        );
+        match submission {
+            SubmissionResult::Correct => eprintln!("That's the right answer!"),
+            SubmissionResult::Incorrect(Some(hint)) => {
+                eprintln!("That's not the right answer (answer is {}).", hint)
+            }
+            SubmissionResult::Incorrect(None) => eprintln!("That's not the right answer."),
+            SubmissionResult::TooSoon(message) => eprintln!("{}", message),
+            SubmissionResult::WrongLevel => {
+                eprintln!("Wrong level -- has this part already been solved?")
+            }
+            SubmissionResult::Unknown(message) => eprintln!("Unexpected response: {}", message),
+        }
+    }
+
+    if json_output {
+        println!("{}", report);
+    }
 }
 
-fn has_arg(arg: &str) -> bool {
-    let args: Vec<String> = env::args().collect();
-    args.contains(&arg.to_string())
+// Usage: "leaderboard <id>", or "leaderboard --file <path>" to render a saved
+// copy of the JSON without fetching anything.
+async fn show_leaderboard(id: Option<&String>) {
+    let json = match (helpers::arg_value("--file"), id) {
+        (Some(path), _) => std::fs::read_to_string(&path).unwrap_or_else(|e| {
+            eprintln!("Could not read {}: {}", path, e);
+            process::exit(1);
+        }),
+        (None, Some(id)) if !id.starts_with("--") => {
+            match helpers::get_leaderboard_json(AOC_YEAR, id).await {
+                Ok(json) => json,
+                Err(e) => {
+                    eprintln!("{}", e);
+                    process::exit(1);
+                }
+            }
+        }
+        _ => {
+            eprintln!("Please pass a leaderboard id, or --file with a saved leaderboard.");
+            process::exit(1);
+        }
+    };
+
+    match leaderboard::parse_leaderboard(&json) {
+        Ok(leaderboard) => print!("{}", leaderboard::render(&leaderboard)),
+        Err(e) => {
+            eprintln!("Could not parse leaderboard: {}", e);
+            process::exit(1);
+        }
+    }
+}
+
+// Usage: "generate <day>", with "--size N" (or "--width W" and "--height H" for
+// grids) and "--seed N". The input goes to stdout, so it can be saved with a
+// redirect.
+fn generate_input(day: Option<&String>) {
+    let day = match day.and_then(|day| day.parse::<u16>().ok()) {
+        Some(day) => day,
+        None => {
+            eprintln!("Please pass the day to generate an input for.");
+            process::exit(1);
+        }
+    };
+    let seed = helpers::parsed_arg::<u64>("--seed").unwrap_or(1);
+    match generate::generate(day, size_from_args(day), &mut Rng::new(seed)) {
+        Some(input) => print!("{}", input),
+        None => {
+            eprintln!("There's no generator for day {}.", day);
+            process::exit(1);
+        }
+    }
+}
+
+fn size_from_args(day: u16) -> Size {
+    let mut size = helpers::parsed_arg::<usize>("--size")
+        .map(Size::new)
+        .unwrap_or_else(|| Size::default_for(day));
+    if let Some(width) = helpers::parsed_arg::<usize>("--width") {
+        size.width = width;
+    }
+    if let Some(height) = helpers::parsed_arg::<usize>("--height") {
+        size.height = height;
+    }
+    size
+}
+
+// Usage: "bench <day>" or "bench all". Times both parts on a generated input
+// (sized and seeded the same way as "generate"), "--runs N" times each, and
+// prints the best and average times. The days' own output still goes to
+// stderr, so redirect it to see just the table.
+fn bench(day: Option<&String>) {
+    let days: Vec<u16> = match day.map(|day| day.as_str()) {
+        Some("all") => (1..=25)
+            .filter(|day| generate::generate(*day, Size::new(1), &mut Rng::new(1)).is_some())
+            .collect(),
+        Some(day) => match day.parse::<u16>() {
+            Ok(day) => vec![day],
+            Err(_) => {
+                eprintln!("Please pass a day number, or \"all\".");
+                process::exit(1);
+            }
+        },
+        None => {
+            eprintln!("Please pass the day to benchmark, or \"all\".");
+            process::exit(1);
+        }
+    };
+    let runs = helpers::parsed_arg::<usize>("--runs").unwrap_or(10).max(1);
+    let seed = helpers::parsed_arg::<u64>("--seed").unwrap_or(1);
+
+    println!(
+        "{:>3}  {:>4}  {:>11}  {:>10}  {:>10}  result",
+        "day", "part", "input bytes", "best ms", "mean ms"
+    );
+    for day in days {
+        let input = match generate::generate(day, size_from_args(day), &mut Rng::new(seed)) {
+            Some(input) => input,
+            None => {
+                eprintln!("There's no generator for day {}.", day);
+                continue;
+            }
+        };
+        for part_two in [false, true] {
+            let mut times = Vec::new();
+            let mut outcome = String::new();
+            for _ in 0..runs {
+                let start = Instant::now();
+                let result = y2022::run_day(day, &input, part_two, &helpers::Args::default());
+                times.push(start.elapsed().as_secs_f64() * 1000.0);
+                outcome = match result {
+                    Ok(answer) if answer.is_solved() => "solved".to_string(),
+                    Ok(_) => "unsolved".to_string(),
+                    Err(e) => format!("error ({})", e),
+                };
+            }
+            let best = times.iter().copied().fold(f64::INFINITY, f64::min);
+            let mean = times.iter().sum::<f64>() / times.len() as f64;
+            println!(
+                "{:>3}  {:>4}  {:>11}  {:>10.3}  {:>10.3}  {}",
+                day,
+                if part_two { 2 } else { 1 },
+                input.len(),
+                best,
+                mean,
+                outcome
+            );
+        }
+    }
+}
+
+// Usage: "fuzz <target>" or "fuzz all", with "--iterations N" (10000 by
+// default) and "--seed N". The targets are day01 to day12, history,
+// leaderboard and stored_answer. Exits with an error if anything panicked.
+fn run_fuzzer(name: Option<&String>) {
+    let targets: Vec<fuzz::Target> = match name.map(|name| name.as_str()) {
+        Some("all") => fuzz::targets(),
+        Some(name) => fuzz::targets()
+            .into_iter()
+            .filter(|target| target.name == name)
+            .collect(),
+        None => Vec::new(),
+    };
+    if targets.is_empty() {
+        let names: Vec<String> = fuzz::targets().into_iter().map(|t| t.name).collect();
+        eprintln!(
+            "Please pass a target to fuzz, or \"all\": {}",
+            names.join(", ")
+        );
+        process::exit(1);
+    }
+    let iterations = helpers::parsed_arg::<usize>("--iterations").unwrap_or(10000);
+    let seed = helpers::parsed_arg::<u64>("--seed").unwrap_or(1);
+
+    // Panics are expected while fuzzing, and reported below, so don't let
+    // every one of them print a message.
+    panic::set_hook(Box::new(|_| {}));
+    let mut crashed = false;
+    for target in targets {
+        let start = Instant::now();
+        match fuzz::fuzz(&target, iterations, &mut Rng::new(seed)) {
+            None => println!(
+                "{}: ok ({} inputs in {:.1}s)",
+                target.name,
+                iterations,
+                start.elapsed().as_secs_f64()
+            ),
+            Some(crash) => {
+                crashed = true;
+                let dir = Path::new(fuzz::CRASH_DIR).join(&target.name);
+                let path = dir.join(format!("{}.txt", helpers::hash_hex(crash.input.as_bytes())));
+                let saved = fs::create_dir_all(&dir).and_then(|_| fs::write(&path, &crash.input));
+                println!("{}: panicked: {}", target.name, crash.message);
+                println!("  input: {:?}", crash.input);
+                match saved {
+                    Ok(_) => println!("  saved to {}", path.display()),
+                    Err(e) => println!("  could not save it to {}: {}", path.display(), e),
+                }
+            }
+        }
+    }
+    let _ = panic::take_hook();
+    if crashed {
+        process::exit(1);
+    }
 }
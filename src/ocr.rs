@@ -0,0 +1,292 @@
+// Reads the block letters that some puzzles draw as their answer. Advent of
+// Code uses two fonts: a small 4x6 one (e.g. 2022 day 10) and a large 6x10 one
+// (e.g. 2018 day 10). Not every letter exists in each font, and anything we
+// don't recognize is reported rather than guessed.
+use std::fmt;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Font {
+    Small,
+    Large,
+}
+
+impl Font {
+    pub fn height(&self) -> usize {
+        match self {
+            Font::Small => 6,
+            Font::Large => 10,
+        }
+    }
+
+    fn glyphs(&self) -> &'static [(char, &'static str)] {
+        match self {
+            Font::Small => SMALL_GLYPHS,
+            Font::Large => LARGE_GLYPHS,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum OcrError {
+    // The lit pixels don't fit either font.
+    UnknownHeight(usize),
+    // Glyph number `index` (0 indexed) isn't a letter we know. Includes the
+    // glyph's pixels so it can be added to the font.
+    UnknownGlyph { index: usize, glyph: String },
+    Empty,
+}
+
+impl fmt::Display for OcrError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            OcrError::UnknownHeight(height) => {
+                write!(
+                    f,
+                    "letters are {} pixels tall, which isn't a known font",
+                    height
+                )
+            }
+            OcrError::UnknownGlyph { index, glyph } => {
+                write!(f, "unknown glyph at letter {}:\n{}", index + 1, glyph)
+            }
+            OcrError::Empty => write!(f, "no lit pixels"),
+        }
+    }
+}
+
+// Reads text drawn with '#' for lit pixels. Any other character is unlit.
+pub fn recognize_art(art: &str) -> Result<String, OcrError> {
+    let pixels: Vec<Vec<bool>> = art
+        .lines()
+        .map(|line| line.chars().map(|c| c == '#').collect())
+        .collect();
+    recognize(&pixels)
+}
+
+// Reads text from a pixel grid. Blank rows around the letters are ignored, and
+// the font is picked based on the height of what's left.
+pub fn recognize(pixels: &[Vec<bool>]) -> Result<String, OcrError> {
+    let lit_rows: Vec<usize> = (0..pixels.len())
+        .filter(|row| pixels[*row].iter().any(|lit| *lit))
+        .collect();
+    let (first, last) = match (lit_rows.first(), lit_rows.last()) {
+        (Some(first), Some(last)) => (*first, *last),
+        _ => return Err(OcrError::Empty),
+    };
+    let rows = &pixels[first..=last];
+
+    let font = match rows.len() {
+        6 => Font::Small,
+        10 => Font::Large,
+        height => return Err(OcrError::UnknownHeight(height)),
+    };
+    recognize_with_font(rows, font)
+}
+
+// Reads text from a pixel grid which is exactly as tall as the font. Letters
+// are separated by at least one blank column.
+pub fn recognize_with_font(pixels: &[Vec<bool>], font: Font) -> Result<String, OcrError> {
+    if pixels.len() != font.height() {
+        return Err(OcrError::UnknownHeight(pixels.len()));
+    }
+    let width = pixels.iter().map(|row| row.len()).max().unwrap_or(0);
+    let lit = |row: usize, col: usize| pixels[row].get(col).copied().unwrap_or(false);
+    let column_is_blank = |col: usize| (0..pixels.len()).all(|row| !lit(row, col));
+
+    let mut text = String::new();
+    let mut col = 0;
+    while col < width {
+        if column_is_blank(col) {
+            col += 1;
+            continue;
+        }
+        let start = col;
+        while col < width && !column_is_blank(col) {
+            col += 1;
+        }
+
+        let glyph = (0..pixels.len())
+            .map(|row| {
+                (start..col)
+                    .map(|c| if lit(row, c) { '#' } else { '.' })
+                    .collect::<String>()
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        match font
+            .glyphs()
+            .iter()
+            .find(|(_, known)| trim_glyph(known) == glyph)
+        {
+            Some((letter, _)) => text.push(*letter),
+            None => {
+                return Err(OcrError::UnknownGlyph {
+                    index: text.chars().count(),
+                    glyph,
+                })
+            }
+        }
+    }
+
+    if text.is_empty() {
+        return Err(OcrError::Empty);
+    }
+    Ok(text)
+}
+
+// Removes blank columns from either side of a glyph from the font tables, so it
+// can be compared with a glyph cut out of a screen.
+fn trim_glyph(glyph: &str) -> String {
+    let rows: Vec<&str> = glyph.split('/').collect();
+    let width = rows[0].len();
+    let blank = |col: usize| rows.iter().all(|row| &row[col..col + 1] == ".");
+    let start = (0..width).find(|col| !blank(*col)).unwrap_or(0);
+    let end = (0..width).rev().find(|col| !blank(*col)).unwrap_or(0) + 1;
+    rows.iter()
+        .map(|row| &row[start..end])
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+// Rows are separated by "/" to keep the tables compact.
+const SMALL_GLYPHS: &[(char, &str)] = &[
+    ('A', ".##./#..#/#..#/####/#..#/#..#"),
+    ('B', "###./#..#/###./#..#/#..#/###."),
+    ('C', ".##./#..#/#.../#.../#..#/.##."),
+    ('E', "####/#.../###./#.../#.../####"),
+    ('F', "####/#.../###./#.../#.../#..."),
+    ('G', ".##./#..#/#.../#.##/#..#/.###"),
+    ('H', "#..#/#..#/####/#..#/#..#/#..#"),
+    ('I', ".###/..#./..#./..#./..#./.###"),
+    ('J', "..##/...#/...#/...#/#..#/.##."),
+    ('K', "#..#/#.#./##../#.#./#.#./#..#"),
+    ('L', "#.../#.../#.../#.../#.../####"),
+    ('O', ".##./#..#/#..#/#..#/#..#/.##."),
+    ('P', "###./#..#/#..#/###./#.../#..."),
+    ('R', "###./#..#/#..#/###./#.#./#..#"),
+    ('S', ".###/#.../#.../.##./...#/###."),
+    ('U', "#..#/#..#/#..#/#..#/#..#/.##."),
+    ('Y', "#...#/#...#/.#.#./..#../..#../..#.."),
+    ('Z', "####/...#/..#./.#../#.../####"),
+];
+
+const LARGE_GLYPHS: &[(char, &str)] = &[
+    (
+        'A',
+        "..##../.#..#./#....#/#....#/#....#/######/#....#/#....#/#....#/#....#",
+    ),
+    (
+        'B',
+        "#####./#....#/#....#/#....#/#####./#....#/#....#/#....#/#....#/#####.",
+    ),
+    (
+        'C',
+        ".####./#....#/#...../#...../#...../#...../#...../#...../#....#/.####.",
+    ),
+    (
+        'E',
+        "######/#...../#...../#...../#####./#...../#...../#...../#...../######",
+    ),
+    (
+        'F',
+        "######/#...../#...../#...../#####./#...../#...../#...../#...../#.....",
+    ),
+    (
+        'G',
+        ".####./#....#/#...../#...../#...../#..###/#....#/#....#/#...##/.###.#",
+    ),
+    (
+        'H',
+        "#....#/#....#/#....#/#....#/######/#....#/#....#/#....#/#....#/#....#",
+    ),
+    (
+        'J',
+        "...###/....#./....#./....#./....#./....#./....#./#...#./#...#./.###..",
+    ),
+    (
+        'K',
+        "#....#/#...#./#..#../#.#.../##..../##..../#.#.../#..#../#...#./#....#",
+    ),
+    (
+        'L',
+        "#...../#...../#...../#...../#...../#...../#...../#...../#...../######",
+    ),
+    (
+        'N',
+        "#....#/##...#/##...#/#.#..#/#.#..#/#..#.#/#..#.#/#...##/#...##/#....#",
+    ),
+    (
+        'P',
+        "#####./#....#/#....#/#....#/#####./#...../#...../#...../#...../#.....",
+    ),
+    (
+        'R',
+        "#####./#....#/#....#/#....#/#####./#..#../#...#./#...#./#....#/#....#",
+    ),
+    (
+        'X',
+        "#....#/#....#/.#..#./.#..#./..##../..##../.#..#./.#..#./#....#/#....#",
+    ),
+    (
+        'Z',
+        "######/.....#/.....#/....#./...#../..#.../.#..../#...../#...../######",
+    ),
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Renders glyphs from a font table side by side with the given spacing.
+    fn draw(font: &[(char, &str)], letters: &str, spacing: usize) -> String {
+        let glyphs: Vec<Vec<&str>> = letters
+            .chars()
+            .map(|c| {
+                let (_, glyph) = font.iter().find(|(letter, _)| *letter == c).unwrap();
+                glyph.split('/').collect()
+            })
+            .collect();
+        (0..glyphs[0].len())
+            .map(|row| {
+                glyphs
+                    .iter()
+                    .map(|g| g[row].to_string())
+                    .collect::<Vec<_>>()
+                    .join(&".".repeat(spacing))
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    #[test]
+    fn test_small_font() {
+        let all: String = SMALL_GLYPHS.iter().map(|(c, _)| *c).collect();
+        assert_eq!(recognize_art(&draw(SMALL_GLYPHS, &all, 1)), Ok(all));
+
+        // The 2022 day 10 screen is 40 pixels wide, with letters every 5 columns.
+        let screen = draw(SMALL_GLYPHS, "RGLRBZAU", 1);
+        assert_eq!(recognize_art(&screen), Ok("RGLRBZAU".to_string()));
+    }
+
+    #[test]
+    fn test_large_font() {
+        let all: String = LARGE_GLYPHS.iter().map(|(c, _)| *c).collect();
+        let art = format!("\n\n{}\n\n", draw(LARGE_GLYPHS, &all, 2));
+        assert_eq!(recognize_art(&art), Ok(all));
+    }
+
+    #[test]
+    fn test_unknown_glyph() {
+        let art = format!("{}\n#...\n", draw(SMALL_GLYPHS, "AB", 1));
+        assert_eq!(recognize_art(&art), Err(OcrError::UnknownHeight(7)));
+
+        let art = "#..#.###\n#..#.#..\n####.##.\n#..#.#..\n#..#.#..\n#..#.###";
+        match recognize_art(art) {
+            Err(OcrError::UnknownGlyph { index, .. }) => assert_eq!(index, 1),
+            other => panic!("Expected unknown glyph, got {:?}", other),
+        }
+
+        assert_eq!(recognize_art("....\n...."), Err(OcrError::Empty));
+    }
+}
@@ -0,0 +1,283 @@
+// Shared helpers for turning puzzle input into typed data. Everything here
+// reports a 1-indexed line number on failure rather than panicking, so a bad
+// input tells you where it went wrong.
+use regex::Regex;
+use std::fmt;
+use std::str::FromStr;
+use std::sync::OnceLock;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    pub line: usize,
+    pub message: String,
+}
+
+impl ParseError {
+    pub fn new(line: usize, message: impl Into<String>) -> ParseError {
+        ParseError {
+            line,
+            message: message.into(),
+        }
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+pub type ParseResult<T> = Result<T, ParseError>;
+
+// Parses a single value, naming it in the error so it's clear which part of
+// the line was wrong.
+pub fn parse_value<T: FromStr>(value: &str, line: usize, what: &str) -> ParseResult<T> {
+    value
+        .trim()
+        .parse::<T>()
+        .map_err(|_| ParseError::new(line, format!("could not parse {} from '{}'", what, value)))
+}
+
+// Returns every integer on the line, in order. Negative numbers are included,
+// and anything that isn't part of a number is ignored.
+pub fn ints<T: FromStr>(line: &str, line_num: usize) -> ParseResult<Vec<T>> {
+    static INT_RE: OnceLock<Regex> = OnceLock::new();
+    let re = INT_RE.get_or_init(|| Regex::new(r"-?\d+").unwrap());
+
+    re.find_iter(line)
+        .map(|m| parse_value(m.as_str(), line_num, "integer"))
+        .collect()
+}
+
+// A group of consecutive non-blank lines.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Paragraph<'a> {
+    // Line number of the first line in the paragraph.
+    pub line: usize,
+    pub lines: Vec<&'a str>,
+}
+
+impl<'a> Paragraph<'a> {
+    // Iterates over the lines along with their line number in the full input.
+    pub fn numbered_lines(&self) -> impl Iterator<Item = (usize, &'a str)> + '_ {
+        self.lines
+            .iter()
+            .enumerate()
+            .map(move |(i, line)| (self.line + i, *line))
+    }
+
+    pub fn text(&self) -> String {
+        self.lines.join("\n")
+    }
+}
+
+// Splits the input into paragraphs separated by one or more blank lines. A
+// missing trailing newline is fine, and so are extra blank lines at the end.
+pub fn paragraphs(input: &str) -> Vec<Paragraph<'_>> {
+    let mut result = Vec::new();
+    let mut current: Option<Paragraph> = None;
+
+    for (i, line) in input.lines().enumerate() {
+        if line.trim().is_empty() {
+            if let Some(paragraph) = current.take() {
+                result.push(paragraph);
+            }
+            continue;
+        }
+        current
+            .get_or_insert_with(|| Paragraph {
+                line: i + 1,
+                lines: Vec::new(),
+            })
+            .lines
+            .push(line);
+    }
+
+    if let Some(paragraph) = current {
+        result.push(paragraph);
+    }
+    result
+}
+
+// Like `paragraphs`, but for inputs which have an exact number of sections.
+pub fn sections<const N: usize>(input: &str) -> ParseResult<[Paragraph<'_>; N]> {
+    let found = paragraphs(input);
+    let count = found.len();
+    found.try_into().map_err(|_| {
+        ParseError::new(
+            1,
+            format!(
+                "expected {} sections separated by blank lines, found {}",
+                N, count
+            ),
+        )
+    })
+}
+
+// A line format with named fields, such as "move {count} from {from} to {to}".
+// Literal text must match exactly, except that any run of whitespace matches
+// any other run of whitespace (including newlines), so patterns can span lines.
+// Compile it once and reuse it for every line.
+pub struct Pattern {
+    template: String,
+    re: Regex,
+}
+
+impl Pattern {
+    pub fn new(template: &str) -> Pattern {
+        static FIELD_RE: OnceLock<Regex> = OnceLock::new();
+        let field_re = FIELD_RE.get_or_init(|| Regex::new(r"\{(\w+)\}").unwrap());
+
+        let mut re_str = String::from(r"^\s*");
+        let mut last_end = 0;
+        for cap in field_re.captures_iter(template) {
+            let whole = cap.get(0).unwrap();
+            re_str.push_str(&literal_to_regex(&template[last_end..whole.start()]));
+            re_str.push_str(&format!(r"(?P<{}>.+?)", &cap[1]));
+            last_end = whole.end();
+        }
+        re_str.push_str(&literal_to_regex(&template[last_end..]));
+        re_str.push_str(r"\s*$");
+
+        Pattern {
+            template: template.to_string(),
+            re: Regex::new(&re_str).expect("Invalid pattern template"),
+        }
+    }
+
+    // Matches the whole string against the pattern. `line` is the line number
+    // the string starts on, used for errors.
+    pub fn parse<'a>(&self, text: &'a str, line: usize) -> ParseResult<Fields<'a>> {
+        match self.re.captures(text) {
+            Some(captures) => Ok(Fields { captures, line }),
+            None => Err(ParseError::new(
+                line,
+                format!("'{}' does not match '{}'", text.trim(), self.template),
+            )),
+        }
+    }
+}
+
+// Escapes the literal parts of a pattern, turning each run of whitespace into
+// a flexible whitespace matcher.
+fn literal_to_regex(literal: &str) -> String {
+    let mut result = String::new();
+    let mut in_whitespace = false;
+    for c in literal.chars() {
+        if c.is_whitespace() {
+            if !in_whitespace {
+                result.push_str(r"\s+");
+            }
+            in_whitespace = true;
+        } else {
+            result.push_str(&regex::escape(&c.to_string()));
+            in_whitespace = false;
+        }
+    }
+    result
+}
+
+// The named fields matched by a `Pattern`.
+pub struct Fields<'a> {
+    captures: regex::Captures<'a>,
+    line: usize,
+}
+
+impl<'a> Fields<'a> {
+    pub fn str(&self, name: &str) -> ParseResult<&'a str> {
+        match self.captures.name(name) {
+            Some(m) => Ok(m.as_str().trim()),
+            None => Err(ParseError::new(
+                self.line,
+                format!("pattern has no field '{}'", name),
+            )),
+        }
+    }
+
+    pub fn get<T: FromStr>(&self, name: &str) -> ParseResult<T> {
+        parse_value(self.str(name)?, self.line, name)
+    }
+}
+
+// Reads a rectangular grid of characters. Leading and trailing whitespace on
+// each line is ignored, as are blank lines, but every row must be the same
+// width.
+pub fn char_grid(input: &str) -> ParseResult<Vec<Vec<char>>> {
+    let mut grid: Vec<Vec<char>> = Vec::new();
+    for (i, line) in input.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        let row: Vec<char> = line.chars().collect();
+        if let Some(first) = grid.first() {
+            if first.len() != row.len() {
+                return Err(ParseError::new(
+                    i + 1,
+                    format!("row has width {}, expected {}", row.len(), first.len()),
+                ));
+            }
+        }
+        grid.push(row);
+    }
+
+    if grid.is_empty() {
+        return Err(ParseError::new(1, "grid is empty"));
+    }
+    Ok(grid)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ints() {
+        assert_eq!(ints::<i32>("x=-3, y=14 and 7", 1), Ok(vec![-3, 14, 7]));
+        assert_eq!(ints::<u8>("no numbers", 1), Ok(vec![]));
+        assert_eq!(ints::<u8>("999", 4).unwrap_err().line, 4);
+    }
+
+    #[test]
+    fn test_paragraphs() {
+        let found = paragraphs("a\nb\n\n\nc\n");
+        assert_eq!(found.len(), 2);
+        assert_eq!(found[0].lines, vec!["a", "b"]);
+        assert_eq!(found[1].line, 5);
+        assert_eq!(found[1].lines, vec!["c"]);
+
+        assert!(sections::<3>("a\n\nb").is_err());
+    }
+
+    #[test]
+    fn test_pattern() {
+        let pattern = Pattern::new("move {count} from {from} to {to}");
+        let fields = pattern.parse("move 12 from 3 to 1", 2).unwrap();
+        assert_eq!(fields.get::<usize>("count"), Ok(12));
+        assert_eq!(fields.get::<usize>("to"), Ok(1));
+        assert!(fields.get::<usize>("nope").is_err());
+
+        let err = pattern.parse("move 1 from 3", 7).err().unwrap();
+        assert_eq!(err.line, 7);
+
+        let fields = pattern.parse("move x from 3 to 1", 3).unwrap();
+        assert_eq!(fields.get::<usize>("count").unwrap_err().line, 3);
+
+        // Whitespace, including newlines, is flexible.
+        let multi = Pattern::new("Monkey {num}:\n  Items: {items}");
+        let fields = multi.parse("Monkey 0:\n      Items: 1, 2", 1).unwrap();
+        assert_eq!(fields.str("items"), Ok("1, 2"));
+    }
+
+    #[test]
+    fn test_char_grid() {
+        assert_eq!(
+            char_grid("ab\ncd\n"),
+            Ok(vec![vec!['a', 'b'], vec!['c', 'd']])
+        );
+        assert_eq!(char_grid("ab\nc").unwrap_err().line, 2);
+        assert!(char_grid("\n").is_err());
+    }
+}
@@ -0,0 +1,106 @@
+// Just enough image support for exporting visualisations. PPM is about the
+// simplest image format there is: a short text header followed by raw RGB
+// bytes. Most image viewers open it, and anything can convert it to PNG.
+use std::fs;
+use std::io;
+
+pub type Rgb = [u8; 3];
+
+pub struct Image {
+    pub width: usize,
+    pub height: usize,
+    pixels: Vec<Rgb>,
+}
+
+impl Image {
+    pub fn new(width: usize, height: usize, background: Rgb) -> Image {
+        Image {
+            width,
+            height,
+            pixels: vec![background; width * height],
+        }
+    }
+
+    pub fn set(&mut self, x: usize, y: usize, color: Rgb) {
+        if x < self.width && y < self.height {
+            self.pixels[y * self.width + x] = color;
+        }
+    }
+
+    // Blows each pixel up into a square, since one pixel per cell is tiny.
+    pub fn scaled(&self, factor: usize) -> Image {
+        let mut scaled = Image::new(self.width * factor, self.height * factor, [0, 0, 0]);
+        for (i, pixel) in scaled.pixels.iter_mut().enumerate() {
+            let (x, y) = (i % (self.width * factor), i / (self.width * factor));
+            *pixel = self.pixels[(y / factor) * self.width + x / factor];
+        }
+        scaled
+    }
+
+    // The binary ("P6") flavour of PPM.
+    pub fn to_ppm(&self) -> Vec<u8> {
+        let mut data = format!("P6\n{} {}\n255\n", self.width, self.height).into_bytes();
+        for pixel in &self.pixels {
+            data.extend_from_slice(pixel);
+        }
+        data
+    }
+
+    pub fn save(&self, path: &str) -> io::Result<()> {
+        fs::write(path, self.to_ppm())
+    }
+}
+
+// A colour for a value between 0 and 1, going from dark blue through red and
+// yellow to white, like a heat map.
+pub fn heat(value: f64) -> Rgb {
+    let stops: [(f64, Rgb); 4] = [
+        (0.0, [20, 20, 60]),
+        (0.4, [200, 30, 30]),
+        (0.8, [250, 220, 40]),
+        (1.0, [255, 255, 255]),
+    ];
+    let value = value.clamp(0.0, 1.0);
+    for pair in stops.windows(2) {
+        let ((start, from), (end, to)) = (pair[0], pair[1]);
+        if value <= end {
+            let t = (value - start) / (end - start);
+            let mix = |a: u8, b: u8| (a as f64 + (b as f64 - a as f64) * t).round() as u8;
+            return [
+                mix(from[0], to[0]),
+                mix(from[1], to[1]),
+                mix(from[2], to[2]),
+            ];
+        }
+    }
+    stops[stops.len() - 1].1
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_to_ppm() {
+        let mut image = Image::new(2, 1, [0, 0, 0]);
+        image.set(1, 0, [255, 128, 1]);
+        // Out of bounds pixels are ignored.
+        image.set(5, 5, [1, 1, 1]);
+        assert_eq!(image.to_ppm(), b"P6\n2 1\n255\n\x00\x00\x00\xff\x80\x01");
+
+        let scaled = image.scaled(2);
+        assert_eq!((scaled.width, scaled.height), (4, 2));
+        assert_eq!(
+            &scaled.to_ppm()[11..],
+            &[0, 0, 0, 0, 0, 0, 255, 128, 1, 255, 128, 1].repeat(2)[..]
+        );
+    }
+
+    #[test]
+    fn test_heat() {
+        assert_eq!(heat(0.0), [20, 20, 60]);
+        assert_eq!(heat(1.0), [255, 255, 255]);
+        assert_eq!(heat(2.0), [255, 255, 255]);
+        assert_eq!(heat(0.8), [250, 220, 40]);
+    }
+}
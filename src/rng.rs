@@ -0,0 +1,95 @@
+// A small seeded random number generator, for making up puzzle inputs. It's
+// SplitMix64, which is tiny, fast and good enough for anything but
+// cryptography. The same seed gives the same numbers on every platform, so a
+// generated input can be recreated from its seed.
+pub struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    pub fn new(seed: u64) -> Rng {
+        Rng { state: seed }
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9e3779b97f4a7c15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+        z ^ (z >> 31)
+    }
+
+    // A number from 0 up to (but not including) n. A plain `% n` would favour
+    // small numbers slightly, so this uses Lemire's multiply and reject method.
+    pub fn below(&mut self, n: u64) -> u64 {
+        assert!(n > 0, "can't pick a number below 0");
+        let threshold = n.wrapping_neg() % n;
+        loop {
+            let product = self.next_u64() as u128 * n as u128;
+            if product as u64 >= threshold {
+                return (product >> 64) as u64;
+            }
+        }
+    }
+
+    // A number between lo and hi, inclusive.
+    pub fn range(&mut self, lo: i64, hi: i64) -> i64 {
+        assert!(lo <= hi, "empty range {}..={}", lo, hi);
+        let span = hi.wrapping_sub(lo) as u64;
+        let offset = match span.checked_add(1) {
+            Some(n) => self.below(n),
+            // The whole of i64.
+            None => self.next_u64(),
+        };
+        lo.wrapping_add(offset as i64)
+    }
+
+    pub fn index(&mut self, len: usize) -> usize {
+        self.below(len as u64) as usize
+    }
+
+    // True with probability p.
+    pub fn chance(&mut self, p: f64) -> bool {
+        // The top 53 bits make an evenly spread f64 in [0, 1).
+        ((self.next_u64() >> 11) as f64 / (1u64 << 53) as f64) < p
+    }
+
+    pub fn pick<'a, T>(&mut self, items: &'a [T]) -> &'a T {
+        &items[self.index(items.len())]
+    }
+
+    // Fisher-Yates.
+    pub fn shuffle<T>(&mut self, items: &mut [T]) {
+        for i in (1..items.len()).rev() {
+            items.swap(i, self.index(i + 1));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rng() {
+        // The reference implementation's first outputs for a seed of 0.
+        let mut rng = Rng::new(0);
+        assert_eq!(rng.next_u64(), 0xe220a8397b1dcdaf);
+        assert_eq!(rng.next_u64(), 0x6e789e6aa1b965f4);
+
+        let mut rng = Rng::new(42);
+        let mut seen = [0; 6];
+        for _ in 0..6000 {
+            seen[rng.range(-2, 3).wrapping_add(2) as usize] += 1;
+        }
+        assert!(seen.iter().all(|count| (800..1200).contains(count)));
+        assert_eq!(rng.range(7, 7), 7);
+        rng.range(i64::MIN, i64::MAX);
+
+        let mut items: Vec<u32> = (0..50).collect();
+        rng.shuffle(&mut items);
+        assert_ne!(items, (0..50).collect::<Vec<_>>());
+        items.sort();
+        assert_eq!(items, (0..50).collect::<Vec<_>>());
+    }
+}
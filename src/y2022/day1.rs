@@ -1,48 +1,279 @@
-pub fn elves_by_food(input: &String, _part_two: bool) -> String {
-    let sections = input.split("\n");
+use crate::answer::Answer;
+use crate::helpers::Args;
+use crate::parsing::{self, ParseError, ParseResult};
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+use std::iter::Enumerate;
+use std::str::Lines;
 
-    // Save food groupings.
-    let mut elves_by_most_food: Vec<u64> = Vec::new();
+pub fn elves_by_food(input: &str, part_two: bool, args: &Args) -> ParseResult<Answer> {
+    // Part one wants the elf with the most food, and part two the top three.
+    // "--top N" sums any number of them.
+    let top = match args.parsed::<usize>("--top") {
+        Ok(top) => top.unwrap_or(if part_two { 3 } else { 1 }),
+        Err(e) => return Ok(Answer::Unsolved(e)),
+    };
+    if top == 0 {
+        return Ok(Answer::Unsolved("--top needs at least 1 elf".to_string()));
+    }
+    // "--report" lists every elf by rank, which means keeping all of them.
+    let report = args.has("--report");
+
+    let mut most_food = TopElves::new(top);
+    let mut all_elves = Vec::new();
+    let mut bad_lines = Vec::new();
+    for elf in Elves::new(input) {
+        match elf {
+            Ok(elf) => {
+                most_food.push(elf);
+                if report {
+                    all_elves.push(elf);
+                }
+            }
+            Err(e) => bad_lines.push(e),
+        }
+    }
+
+    // Every bad line is listed, rather than stopping at the first.
+    if let Some(first) = bad_lines.first() {
+        for bad_line in &bad_lines {
+            eprintln!("Bad line: {}", bad_line);
+        }
+        return Err(first.clone());
+    }
+
+    if report {
+        print_report(&all_elves);
+    }
+
+    let top_elves = most_food.into_sorted();
+    if top_elves.is_empty() {
+        return Ok(Answer::Unsolved("there are no elves".to_string()));
+    }
+    if top_elves.len() < top {
+        eprintln!(
+            "Only {} elves are carrying food, so summing all of them.",
+            top_elves.len()
+        );
+    }
+    for (i, elf) in top_elves.iter().enumerate() {
+        eprintln!(
+            "#{}: elf {} (line {}) with {}",
+            i + 1,
+            elf.number,
+            elf.line,
+            elf.calories
+        );
+    }
+
+    let Some(total_top_food) = top_elves
+        .iter()
+        .try_fold(0u64, |total, elf| total.checked_add(elf.calories))
+    else {
+        return Ok(Answer::Unsolved("too many calories to add up".to_string()));
+    };
+    eprintln!("Top {} elves summed: {}", top_elves.len(), total_top_food);
+    Ok(total_top_food.into())
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Elf {
+    // Elves are numbered from 1 in the order they appear.
+    number: usize,
+    // The line the elf's food starts on.
+    line: usize,
+    calories: u64,
+}
+
+// Reads one elf at a time, each a group of lines separated by blank lines. A
+// line which isn't a number comes out as an error, and the elf carries on
+// without it.
+pub struct Elves<'a> {
+    lines: Enumerate<Lines<'a>>,
+    current: Option<Elf>,
+    count: usize,
+}
+
+impl<'a> Elves<'a> {
+    pub fn new(input: &'a str) -> Elves<'a> {
+        Elves {
+            lines: input.lines().enumerate(),
+            current: None,
+            count: 0,
+        }
+    }
+}
 
-    sections.fold(0, |acc: u64, individual_food: &str| {
-        // If can't be parsed, assume 0 -- likely the empty string in that case.
-        let caloric_value = match individual_food.parse::<u64>() {
-            Ok(calories) => calories,
-            Err(_) => 0,
-        };
+impl Iterator for Elves<'_> {
+    type Item = ParseResult<Elf>;
 
-        let total_elf_food = acc + caloric_value;
+    fn next(&mut self) -> Option<ParseResult<Elf>> {
+        loop {
+            // Running out of input ends the last elf, whether or not there's
+            // a newline after it.
+            let Some((i, line)) = self.lines.next() else {
+                return self.current.take().map(Ok);
+            };
+            let (line_num, line) = (i + 1, line.trim());
+            if line.is_empty() {
+                match self.current.take() {
+                    Some(elf) => return Some(Ok(elf)),
+                    None => continue,
+                }
+            }
 
-        // When we reach an empty value, we've accumulated all the food for the
-        // current elf. At this point, we can maintain a sorted list.
-        if individual_food.is_empty() {
-            let pos = elves_by_most_food
-                .binary_search(&total_elf_food)
-                .unwrap_or_else(|e| e);
+            let elf = self.current.get_or_insert_with(|| {
+                self.count += 1;
+                Elf {
+                    number: self.count,
+                    line: line_num,
+                    calories: 0,
+                }
+            });
+            let calories =
+                parsing::parse_value::<u64>(line, line_num, "calories").and_then(|food| {
+                    elf.calories
+                        .checked_add(food)
+                        .ok_or_else(|| ParseError::new(line_num, "too many calories to add up"))
+                });
+            match calories {
+                Ok(calories) => elf.calories = calories,
+                Err(e) => return Some(Err(e)),
+            }
+        }
+    }
+}
 
-            elves_by_most_food.insert(pos, total_elf_food);
+// Keeps the `limit` elves with the most food seen so far. The heap is a
+// min-heap of at most `limit` elves, so each new elf only has to beat the
+// smallest of them.
+struct TopElves {
+    limit: usize,
+    // Calories, elf number and line. Ties go to the elf which came first.
+    heap: BinaryHeap<Reverse<(u64, Reverse<usize>, usize)>>,
+}
 
-            // Now, reset the accumulator for the next elf.
-            return 0;
+impl TopElves {
+    fn new(limit: usize) -> TopElves {
+        TopElves {
+            limit,
+            // "--top" can be huge, so the heap only grows as elves turn up.
+            heap: BinaryHeap::new(),
         }
+    }
 
-        // Simply accumulating the food for the current elf.
-        return total_elf_food;
-    });
+    fn push(&mut self, elf: Elf) {
+        self.heap
+            .push(Reverse((elf.calories, Reverse(elf.number), elf.line)));
+        if self.heap.len() > self.limit {
+            self.heap.pop();
+        }
+    }
 
-    // Grab the last three elves and print them.
-    let top_three_elves = &elves_by_most_food[elves_by_most_food.len() - 3..];
+    // The elves kept, most food first.
+    fn into_sorted(self) -> Vec<Elf> {
+        self.heap
+            .into_sorted_vec()
+            .into_iter()
+            .map(|Reverse((calories, Reverse(number), line))| Elf {
+                number,
+                line,
+                calories,
+            })
+            .collect()
+    }
+}
 
-    // Show the top three, which means reversing our little iterator.
-    for (i, top_food) in top_three_elves.iter().rev().enumerate() {
-        println!("Elf {}: {}", i + 1, top_food);
+// Lists every elf from most food to least. Elves with the same amount share a
+// rank.
+fn print_report(elves: &[Elf]) {
+    let mut ranked = elves.to_vec();
+    ranked.sort_by_key(|elf| (Reverse(elf.calories), elf.number));
+    let mut rank = 0;
+    for (i, elf) in ranked.iter().enumerate() {
+        if i == 0 || ranked[i - 1].calories != elf.calories {
+            rank = i + 1;
+        }
+        eprintln!(
+            "Rank {:>4}: elf {:>4} (line {:>5}) with {}",
+            rank, elf.number, elf.line, elf.calories
+        );
     }
+}
 
-    let total_top_food: u64 = top_three_elves.iter().sum();
-    println!(
-        "Elf with most food (P1): {}. Top three elves summed (P2): {}",
-        top_three_elves[2], total_top_food
-    );
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-    return total_top_food.to_string();
+    fn totals(input: &str) -> Vec<ParseResult<u64>> {
+        Elves::new(input)
+            .map(|elf| elf.map(|elf| elf.calories))
+            .collect()
+    }
+
+    #[test]
+    fn test_elves() {
+        assert_eq!(totals("1\n2\n\n\n3\n\n"), vec![Ok(3), Ok(3)]);
+        // No trailing newline, and blank lines with spaces in.
+        assert_eq!(totals("\n1\n  \n4\n5"), vec![Ok(1), Ok(9)]);
+        assert_eq!(totals(""), vec![]);
+
+        // Bad lines are errors, but reading carries on after them.
+        let bad: Vec<usize> = totals("1\nx\n\n2\n-3\n18446744073709551615")
+            .into_iter()
+            .filter_map(|total| total.err())
+            .map(|e| e.line)
+            .collect();
+        assert_eq!(bad, vec![2, 5, 6]);
+    }
+
+    #[test]
+    fn test_top_elves() {
+        let mut top = TopElves::new(3);
+        for (number, calories) in [6000, 4000, 11000, 24000, 10000, 11000].iter().enumerate() {
+            top.push(Elf {
+                number: number + 1,
+                line: 0,
+                calories: *calories,
+            });
+        }
+        let sorted: Vec<(usize, u64)> = top
+            .into_sorted()
+            .iter()
+            .map(|elf| (elf.number, elf.calories))
+            .collect();
+        assert_eq!(sorted, vec![(4, 24000), (3, 11000), (6, 11000)]);
+
+        // Fewer elves than asked for is fine.
+        let mut top = TopElves::new(5);
+        top.push(Elf {
+            number: 1,
+            line: 1,
+            calories: 7,
+        });
+        assert_eq!(top.into_sorted().len(), 1);
+        assert!(TopElves::new(0).into_sorted().is_empty());
+        assert!(TopElves::new(usize::MAX).into_sorted().is_empty());
+    }
+
+    #[test]
+    fn test_options() {
+        let input = "1\n\n2\n\n3\n";
+        let top = |value: &str| elves_by_food(input, false, &Args::new(&["--top", value]));
+        assert_eq!(top("2"), Ok(Answer::Int(5)));
+        assert_eq!(top("99"), Ok(Answer::Int(6)));
+        // Bad values are reported, rather than ending the whole program.
+        assert!(matches!(top("0"), Ok(Answer::Unsolved(_))));
+        assert!(matches!(top("x"), Ok(Answer::Unsolved(_))));
+    }
+
+    #[test]
+    fn test_huge_totals() {
+        // Each elf fits, but the top three don't add up.
+        let input = "18446744073709551615\n\n18446744073709551615\n";
+        assert!(matches!(
+            elves_by_food(input, true, &Args::default()),
+            Ok(Answer::Unsolved(_))
+        ));
+    }
 }
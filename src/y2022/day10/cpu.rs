@@ -0,0 +1,150 @@
+// A tiny emulator for the handheld device's CPU. Instructions take a fixed
+// number of cycles, and observers can look at the registers "during" each
+// cycle, before the instruction running in that cycle has finished.
+use crate::parsing::{self, ParseError, ParseResult};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Instruction {
+    Noop,
+    Addx(i32),
+}
+
+impl Instruction {
+    // The number of cycles the instruction takes to complete.
+    pub fn cycles(&self) -> u32 {
+        match self {
+            Instruction::Noop => 1,
+            Instruction::Addx(_) => 2,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Registers {
+    pub x: i32,
+}
+
+impl Default for Registers {
+    fn default() -> Registers {
+        Registers { x: 1 }
+    }
+}
+
+// Called once per cycle, with the cycle number (starting at 1) and the
+// register values during that cycle.
+pub trait CycleObserver {
+    fn during(&mut self, cycle: u32, registers: &Registers);
+}
+
+#[derive(Default)]
+pub struct Cpu {
+    pub registers: Registers,
+    // The number of cycles which have completed.
+    pub cycle: u32,
+}
+
+impl Cpu {
+    // Fails if a register overflows, since no real program would do that.
+    pub fn execute(
+        &mut self,
+        instruction: Instruction,
+        observers: &mut [&mut dyn CycleObserver],
+    ) -> Result<(), String> {
+        for _ in 0..instruction.cycles() {
+            self.cycle += 1;
+            for observer in observers.iter_mut() {
+                observer.during(self.cycle, &self.registers);
+            }
+        }
+
+        // The instruction's effect only shows up after its last cycle.
+        match instruction {
+            Instruction::Noop => (),
+            Instruction::Addx(value) => {
+                self.registers.x = self.registers.x.checked_add(value).ok_or_else(|| {
+                    format!("X overflowed adding {} at cycle {}", value, self.cycle)
+                })?
+            }
+        }
+        Ok(())
+    }
+
+    pub fn run(
+        &mut self,
+        program: &[Instruction],
+        observers: &mut [&mut dyn CycleObserver],
+    ) -> Result<(), String> {
+        for instruction in program {
+            self.execute(*instruction, observers)?;
+        }
+        Ok(())
+    }
+}
+
+pub fn parse_program(input: &str) -> ParseResult<Vec<Instruction>> {
+    let mut program = Vec::new();
+    for (i, line) in input.lines().enumerate() {
+        let line_num = i + 1;
+        let mut parts = line.split_whitespace();
+        let instruction = match parts.next() {
+            None => continue,
+            Some("noop") => Instruction::Noop,
+            Some("addx") => match parts.next() {
+                Some(value) => {
+                    Instruction::Addx(parsing::parse_value(value, line_num, "addx value")?)
+                }
+                None => return Err(ParseError::new(line_num, "addx needs a value")),
+            },
+            Some(other) => {
+                return Err(ParseError::new(
+                    line_num,
+                    format!("unsupported command name '{}'", other),
+                ))
+            }
+        };
+        if let Some(extra) = parts.next() {
+            return Err(ParseError::new(
+                line_num,
+                format!("unexpected argument '{}'", extra),
+            ));
+        }
+        program.push(instruction);
+    }
+    Ok(program)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct RecordX(Vec<i32>);
+
+    impl CycleObserver for RecordX {
+        fn during(&mut self, _cycle: u32, registers: &Registers) {
+            self.0.push(registers.x);
+        }
+    }
+
+    #[test]
+    fn test_small_program() {
+        let program = parse_program("noop\naddx 3\naddx -5\n").unwrap();
+        let mut cpu = Cpu::default();
+        let mut record = RecordX(Vec::new());
+        cpu.run(&program, &mut [&mut record]).unwrap();
+
+        assert_eq!(cpu.cycle, 5);
+        assert_eq!(cpu.registers.x, -1);
+        // X only changes after addx finishes its second cycle.
+        assert_eq!(record.0, vec![1, 1, 1, 4, 4]);
+
+        let program = parse_program("addx 2147483647").unwrap();
+        assert!(Cpu::default().run(&program, &mut []).is_err());
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert_eq!(parse_program("noop\njump 3").unwrap_err().line, 2);
+        assert!(parse_program("addx").is_err());
+        assert!(parse_program("addx 1 2").is_err());
+    }
+}
@@ -0,0 +1,159 @@
+use crate::answer::Answer;
+use crate::helpers::Args;
+use crate::ocr;
+use crate::parsing::ParseResult;
+use cpu::{Cpu, CycleObserver, Registers};
+
+pub mod cpu;
+
+pub fn signal_strength(input: &str, part_two: bool, _args: &Args) -> ParseResult<Answer> {
+    let program = cpu::parse_program(input)?;
+
+    // Part one samples the signal, and part two draws on the screen. Both watch
+    // the same run of the program.
+    let mut sampler = SignalSampler::new(20, 40);
+    let mut crt = Crt::new(40, 6);
+    let mut cpu = Cpu::default();
+    if let Err(e) = cpu.run(&program, &mut [&mut sampler, &mut crt]) {
+        return Ok(Answer::Unsolved(e));
+    }
+
+    eprintln!("\nCycles: {}", cpu.cycle);
+    let screen = crt.render();
+    eprintln!("{}", screen);
+
+    if part_two {
+        // The screen spells out the answer. If we can't read it, a human can.
+        match ocr::recognize_art(&screen) {
+            Ok(text) => Ok(Answer::Text(text)),
+            Err(e) => {
+                eprintln!("Could not read the screen: {}", e);
+                Ok(Answer::Art(screen))
+            }
+        }
+    } else {
+        let total = sampler
+            .samples
+            .iter()
+            .try_fold(0i64, |total, sample| total.checked_add(*sample));
+        Ok(match total {
+            Some(total) => total.into(),
+            None => Answer::Unsolved("the signal strengths are too big to add up".to_string()),
+        })
+    }
+}
+
+// Records the signal strength (cycle number times X) at the first cycle, and
+// then periodically after that. E.g. 20, 60, 100, and so on.
+struct SignalSampler {
+    first: u32,
+    every: u32,
+    // Big enough for any X times any cycle number.
+    samples: Vec<i64>,
+}
+
+impl SignalSampler {
+    fn new(first: u32, every: u32) -> SignalSampler {
+        SignalSampler {
+            first,
+            every,
+            samples: Vec::new(),
+        }
+    }
+}
+
+impl CycleObserver for SignalSampler {
+    fn during(&mut self, cycle: u32, registers: &Registers) {
+        if cycle >= self.first && (cycle - self.first).is_multiple_of(self.every) {
+            self.samples.push(registers.x as i64 * cycle as i64);
+        }
+    }
+}
+
+// The screen draws one pixel per cycle, left to right and then top to bottom.
+// A pixel is lit if the three pixel wide sprite (centered on X) covers it.
+struct Crt {
+    width: usize,
+    height: usize,
+    pixels: Vec<bool>,
+}
+
+impl Crt {
+    fn new(width: usize, height: usize) -> Crt {
+        Crt {
+            width,
+            height,
+            pixels: vec![false; width * height],
+        }
+    }
+
+    fn render(&self) -> String {
+        self.pixels
+            .chunks(self.width)
+            .map(|row| row.iter().map(|lit| if *lit { '#' } else { '.' }).collect())
+            .collect::<Vec<String>>()
+            .join("\n")
+    }
+}
+
+impl CycleObserver for Crt {
+    fn during(&mut self, cycle: u32, registers: &Registers) {
+        // Positions are 0 indexed, but cycles are 1 indexed. So we need to adjust
+        // slightly. Programs longer than one frame wrap around to the top.
+        let pixel = (cycle as usize - 1) % (self.width * self.height);
+        let column = (pixel % self.width) as i32;
+
+        // Is visible if sprite position is at or within one of the pixel position.
+        self.pixels[pixel] = registers.x.abs_diff(column) <= 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = include_str!("../../../fuzz/corpus/day10/example.txt");
+
+    #[test]
+    fn test_example() {
+        assert_eq!(
+            signal_strength(EXAMPLE, false, &Args::default()).unwrap(),
+            Answer::Int(13140)
+        );
+
+        // The example's screen is stripes rather than letters, so it can't be
+        // read and comes back as art.
+        let screen = [
+            "##..##..##..##..##..##..##..##..##..##..",
+            "###...###...###...###...###...###...###.",
+            "####....####....####....####....####....",
+            "#####.....#####.....#####.....#####.....",
+            "######......######......######......####",
+            "#######.......#######.......#######.....",
+        ];
+        assert_eq!(
+            signal_strength(EXAMPLE, true, &Args::default()).unwrap(),
+            Answer::Art(screen.join("\n"))
+        );
+    }
+
+    #[test]
+    fn test_sampler_and_crt() {
+        let mut sampler = SignalSampler::new(2, 3);
+        let mut crt = Crt::new(3, 2);
+        for (cycle, x) in [1, 1, 2, -1, 0, 5, 9].into_iter().enumerate() {
+            let registers = Registers { x };
+            sampler.during(cycle as u32 + 1, &registers);
+            crt.during(cycle as u32 + 1, &registers);
+        }
+        assert_eq!(sampler.samples, vec![2, 0]);
+        // The seventh cycle wraps round to the first pixel.
+        assert_eq!(crt.render(), ".##\n##.");
+
+        // Huge X values don't overflow.
+        let mut sampler = SignalSampler::new(1, 1);
+        sampler.during(u32::MAX, &Registers { x: i32::MAX });
+        assert_eq!(sampler.samples, vec![i32::MAX as i64 * u32::MAX as i64]);
+        crt.during(1, &Registers { x: i32::MIN });
+    }
+}
@@ -0,0 +1,315 @@
+// Monkey operations, like "old * 19" or "(old + 3) * old", parsed once into a
+// small expression tree so they don't have to be re-read for every item.
+use std::fmt;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Op {
+    Add,
+    Sub,
+    Mul,
+    Div,
+}
+
+impl Op {
+    fn symbol(&self) -> char {
+        match self {
+            Op::Add => '+',
+            Op::Sub => '-',
+            Op::Mul => '*',
+            Op::Div => '/',
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Expr {
+    Old,
+    Num(u64),
+    Binary(Box<Expr>, Op, Box<Expr>),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EvalError {
+    Overflow { left: u64, op: Op, right: u64 },
+    DivideByZero,
+    NotModular,
+}
+
+impl fmt::Display for EvalError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EvalError::Overflow { left, op, right } => write!(
+                f,
+                "arithmetic overflow evaluating {} {} {}",
+                left,
+                op.symbol(),
+                right
+            ),
+            EvalError::DivideByZero => write!(f, "division by zero"),
+            EvalError::NotModular => write!(f, "division can't be done modulo a number"),
+        }
+    }
+}
+
+impl Expr {
+    // Evaluates the expression for the given value of "old". Subtraction below
+    // zero counts as overflow, since worry levels can't be negative.
+    pub fn eval(&self, old: u64) -> Result<u64, EvalError> {
+        match self {
+            Expr::Old => Ok(old),
+            Expr::Num(n) => Ok(*n),
+            Expr::Binary(left, op, right) => {
+                let (left, right) = (left.eval(old)?, right.eval(old)?);
+                let result = match op {
+                    Op::Add => left.checked_add(right),
+                    Op::Sub => left.checked_sub(right),
+                    Op::Mul => left.checked_mul(right),
+                    Op::Div if right == 0 => return Err(EvalError::DivideByZero),
+                    Op::Div => left.checked_div(right),
+                };
+                result.ok_or(EvalError::Overflow {
+                    left,
+                    op: *op,
+                    right,
+                })
+            }
+        }
+    }
+
+    // Evaluates the expression modulo n, for an "old" which has already been
+    // reduced. Subtraction wraps round like the rest, so it stands in for the
+    // real (much bigger) worry level rather than going below zero. Only works
+    // for modular expressions.
+    pub fn eval_mod(&self, old: u64, n: u64) -> Result<u64, EvalError> {
+        match self {
+            Expr::Old => Ok(old % n),
+            Expr::Num(num) => Ok(num % n),
+            Expr::Binary(left, op, right) => {
+                // Both sides are below n, so none of this can overflow a u128.
+                let left = left.eval_mod(old, n)? as u128;
+                let right = right.eval_mod(old, n)? as u128;
+                let n = n as u128;
+                let result = match op {
+                    Op::Add => left + right,
+                    Op::Sub => left + n - right,
+                    Op::Mul => left * right,
+                    Op::Div => return Err(EvalError::NotModular),
+                };
+                Ok((result % n) as u64)
+            }
+        }
+    }
+
+    // True if the expression only uses operations which keep working when the
+    // value is reduced modulo some number first. Division doesn't.
+    pub fn is_modular(&self) -> bool {
+        match self {
+            Expr::Old | Expr::Num(_) => true,
+            Expr::Binary(left, op, right) => {
+                *op != Op::Div && left.is_modular() && right.is_modular()
+            }
+        }
+    }
+}
+
+impl fmt::Display for Expr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Expr::Old => write!(f, "old"),
+            Expr::Num(n) => write!(f, "{}", n),
+            Expr::Binary(left, op, right) => write!(f, "({} {} {})", left, op.symbol(), right),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Token {
+    Old,
+    Num(u64),
+    Op(Op),
+    Open,
+    Close,
+}
+
+fn tokenize(text: &str) -> Result<Vec<Token>, String> {
+    let mut tokens = Vec::new();
+    let mut chars = text.chars().peekable();
+    while let Some(&c) = chars.peek() {
+        match c {
+            c if c.is_whitespace() => {
+                chars.next();
+            }
+            '+' | '-' | '*' | '/' => {
+                chars.next();
+                tokens.push(Token::Op(match c {
+                    '+' => Op::Add,
+                    '-' => Op::Sub,
+                    '*' => Op::Mul,
+                    _ => Op::Div,
+                }));
+            }
+            '(' | ')' => {
+                chars.next();
+                tokens.push(if c == '(' { Token::Open } else { Token::Close });
+            }
+            '0'..='9' => {
+                let mut digits = String::new();
+                while let Some(d) = chars.peek().filter(|d| d.is_ascii_digit()) {
+                    digits.push(*d);
+                    chars.next();
+                }
+                let n = digits
+                    .parse::<u64>()
+                    .map_err(|_| format!("number '{}' is too large", digits))?;
+                tokens.push(Token::Num(n));
+            }
+            c if c.is_alphabetic() => {
+                let mut word = String::new();
+                while let Some(w) = chars.peek().filter(|w| w.is_alphanumeric()) {
+                    word.push(*w);
+                    chars.next();
+                }
+                if word != "old" {
+                    return Err(format!("unknown variable '{}'", word));
+                }
+                tokens.push(Token::Old);
+            }
+            other => return Err(format!("unexpected character '{}'", other)),
+        }
+    }
+    Ok(tokens)
+}
+
+// Expressions can't nest deeper than this, counting both brackets and chains
+// of operators. Evaluating (or even dropping) a very deep tree would overflow
+// the stack, and no monkey needs anything like this many.
+const MAX_DEPTH: usize = 100;
+
+// Parses an expression with the usual precedence: * and / bind tighter than
+// + and -, and operators of the same precedence are left associative.
+pub fn parse(text: &str) -> Result<Expr, String> {
+    let tokens = tokenize(text)?;
+    let mut pos = 0;
+    let expr = parse_sum(&tokens, &mut pos, 0)?;
+    match tokens.get(pos) {
+        None => Ok(expr),
+        Some(token) => Err(format!("unexpected {:?} in '{}'", token, text.trim())),
+    }
+}
+
+// `depth` is how deep the expression being parsed is inside the whole tree.
+// Each operator in a chain pushes the ones before it one level deeper.
+fn parse_sum(tokens: &[Token], pos: &mut usize, depth: usize) -> Result<Expr, String> {
+    let mut expr = parse_product(tokens, pos, depth)?;
+    let mut depth = depth;
+    while let Some(Token::Op(op @ (Op::Add | Op::Sub))) = tokens.get(*pos) {
+        *pos += 1;
+        depth = deeper(depth)?;
+        let right = parse_product(tokens, pos, depth)?;
+        expr = Expr::Binary(Box::new(expr), *op, Box::new(right));
+    }
+    Ok(expr)
+}
+
+fn parse_product(tokens: &[Token], pos: &mut usize, depth: usize) -> Result<Expr, String> {
+    let mut expr = parse_atom(tokens, pos, depth)?;
+    let mut depth = depth;
+    while let Some(Token::Op(op @ (Op::Mul | Op::Div))) = tokens.get(*pos) {
+        *pos += 1;
+        depth = deeper(depth)?;
+        let right = parse_atom(tokens, pos, depth)?;
+        expr = Expr::Binary(Box::new(expr), *op, Box::new(right));
+    }
+    Ok(expr)
+}
+
+fn parse_atom(tokens: &[Token], pos: &mut usize, depth: usize) -> Result<Expr, String> {
+    let token = tokens.get(*pos).ok_or("expression ended early")?;
+    *pos += 1;
+    match token {
+        Token::Old => Ok(Expr::Old),
+        Token::Num(n) => Ok(Expr::Num(*n)),
+        Token::Open => {
+            let expr = parse_sum(tokens, pos, deeper(depth)?)?;
+            match tokens.get(*pos) {
+                Some(Token::Close) => {
+                    *pos += 1;
+                    Ok(expr)
+                }
+                _ => Err("missing closing parenthesis".to_string()),
+            }
+        }
+        other => Err(format!("unexpected {:?}", other)),
+    }
+}
+
+fn deeper(depth: usize) -> Result<usize, String> {
+    if depth >= MAX_DEPTH {
+        Err(format!("expression nests more than {} deep", MAX_DEPTH))
+    } else {
+        Ok(depth + 1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_and_eval() {
+        assert_eq!(parse("old * 19").unwrap().eval(79), Ok(1501));
+        assert_eq!(parse("old * old").unwrap().eval(60), Ok(3600));
+        assert_eq!(parse("2 + old * 3").unwrap().eval(4), Ok(14));
+        assert_eq!(parse("(2 + old) * 3").unwrap().eval(4), Ok(18));
+        assert_eq!(parse("old - 10 - 2").unwrap().eval(20), Ok(8));
+        assert_eq!(parse("old / (1 + 1)").unwrap().eval(7), Ok(3));
+    }
+
+    #[test]
+    fn test_eval_mod() {
+        assert_eq!(parse("old - 5").unwrap().eval_mod(3, 7), Ok(5));
+        assert_eq!(parse("old * old + 1").unwrap().eval_mod(6, 7), Ok(2));
+        assert_eq!(
+            parse("old * old").unwrap().eval_mod(u64::MAX - 1, u64::MAX),
+            Ok(1)
+        );
+        assert_eq!(
+            parse("old / 2").unwrap().eval_mod(4, 7),
+            Err(EvalError::NotModular)
+        );
+    }
+
+    #[test]
+    fn test_eval_errors() {
+        assert_eq!(
+            parse("old * old").unwrap().eval(u64::MAX),
+            Err(EvalError::Overflow {
+                left: u64::MAX,
+                op: Op::Mul,
+                right: u64::MAX
+            })
+        );
+        assert!(parse("old - 5").unwrap().eval(3).is_err());
+        assert_eq!(
+            parse("old / (old - 1)").unwrap().eval(1),
+            Err(EvalError::DivideByZero)
+        );
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert!(parse("old ^ 2").is_err());
+        assert!(parse("new + 1").is_err());
+        assert!(parse("(old + 1").is_err());
+        assert!(parse("old +").is_err());
+        assert!(parse("old 1").is_err());
+        assert!(parse("").is_err());
+
+        // Deep nesting is an error rather than a stack overflow.
+        assert!(parse(&"(".repeat(200_000)).is_err());
+        assert!(parse(&"old + ".repeat(200_000)).is_err());
+        assert!(parse(&format!("{}old{}", "(".repeat(50), ")".repeat(50))).is_ok());
+        assert!(parse(&format!("{}old", "old * 2 + ".repeat(50))).is_ok());
+        assert!(parse(&format!("{}old", "old * 2 + ".repeat(150))).is_err());
+    }
+}
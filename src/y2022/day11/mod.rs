@@ -0,0 +1,399 @@
+use crate::answer::Answer;
+use crate::helpers::Args;
+use crate::parsing::{self, ParseError, ParseResult, Pattern};
+use expr::Expr;
+use std::collections::HashMap;
+
+mod expr;
+
+#[derive(Debug, Clone)]
+pub struct Monkey {
+    items_worry: Vec<u64>,
+    operation: Expr,
+    divisible_by: u64,
+    true_to: usize,
+    false_to: usize,
+    monkey_num: usize,
+}
+
+// How worry levels are kept in check after each inspection.
+#[derive(Debug, Clone, Copy)]
+enum Relief {
+    // Part one: worry is divided by three (rounding down).
+    DivideBy(u64),
+    // Part two: worry is reduced modulo the product of every monkey's divisor,
+    // which leaves the result of every divisibility test unchanged.
+    Modulo(u64),
+}
+
+pub fn day_11(input: &str, part_two: bool, args: &Args) -> ParseResult<Answer> {
+    // let input = "Monkey 0:
+    //     Starting items: 79, 98
+    //     Operation: new = old * 19
+    //     Test: divisible by 23
+    //       If true: throw to monkey 2
+    //       If false: throw to monkey 3
+    //   ...
+
+    let monkeys: Vec<Monkey> = input_to_monkeys(input)?;
+    // Monkey business is the two busiest monkeys' counts multiplied.
+    if monkeys.len() < 2 {
+        return Ok(Answer::Unsolved(format!(
+            "monkey business needs at least two monkeys, not {}",
+            monkeys.len()
+        )));
+    }
+
+    for monkey in &monkeys {
+        eprintln!("Monkey: {:?}", monkey);
+    }
+
+    let relief = if part_two {
+        if let Some(monkey) = monkeys.iter().find(|m| !m.operation.is_modular()) {
+            return Ok(Answer::Unsolved(format!(
+                "monkey {}'s operation {} can't be used with modular worry levels",
+                monkey.monkey_num, monkey.operation
+            )));
+        }
+        match monkeys
+            .iter()
+            .try_fold(1u64, |acc, monkey| acc.checked_mul(monkey.divisible_by))
+        {
+            Some(product) => Relief::Modulo(product),
+            None => {
+                return Ok(Answer::Unsolved(
+                    "product of the divisors overflows".to_string(),
+                ))
+            }
+        }
+    } else {
+        Relief::DivideBy(3)
+    };
+
+    // Use "--rounds" to simulate a different number of rounds. Thanks to cycle
+    // detection, even billions of rounds are quick.
+    let num_rounds = match args.parsed::<u64>("--rounds") {
+        Ok(num_rounds) => num_rounds.unwrap_or(if part_two { 10000 } else { 20 }),
+        Err(e) => return Ok(Answer::Unsolved(e)),
+    };
+
+    let mut times_inspected = match simulate(&monkeys, relief, num_rounds) {
+        Ok(times_inspected) => times_inspected,
+        Err(e) => return Ok(Answer::Unsolved(e)),
+    };
+
+    for (monkey, times) in monkeys.iter().zip(&times_inspected) {
+        eprintln!(
+            "Monkey: {} inspected items {} times.",
+            monkey.monkey_num, times
+        );
+    }
+
+    times_inspected.sort_unstable();
+    let monkey_business = times_inspected[times_inspected.len() - 1] as u128
+        * times_inspected[times_inspected.len() - 2] as u128;
+
+    Ok(monkey_business.into())
+}
+
+// Returns the number of times each monkey inspects an item over the given
+// number of rounds. Items never affect each other, so each one is followed on
+// its own.
+fn simulate(monkeys: &[Monkey], relief: Relief, num_rounds: u64) -> Result<Vec<u64>, String> {
+    let mut times_inspected = vec![0u64; monkeys.len()];
+    for monkey in monkeys {
+        for item in &monkey.items_worry {
+            follow_item(
+                monkeys,
+                relief,
+                num_rounds,
+                monkey.monkey_num,
+                *item,
+                &mut times_inspected,
+            )?;
+        }
+    }
+    Ok(times_inspected)
+}
+
+// Follows a single item from monkey to monkey, adding each inspection to
+// times_inspected. An item's future only depends on which monkey holds it and
+// its worry level, so once that state repeats we know the item is in a loop
+// and can skip ahead by whole loops at a time.
+fn follow_item(
+    monkeys: &[Monkey],
+    relief: Relief,
+    num_rounds: u64,
+    start_monkey: usize,
+    start_worry: u64,
+    times_inspected: &mut [u64],
+) -> Result<(), String> {
+    // Every (monkey, worry) state the item has been in, and the round it was in
+    // at the time. The index into `visits` is stored so a loop can be replayed.
+    let mut seen: HashMap<(usize, u64), usize> = HashMap::new();
+    let mut visits: Vec<(usize, u64)> = Vec::new();
+
+    // Reducing the starting worry doesn't change any divisibility tests, and
+    // means states line up with the reduced ones found later.
+    let start_worry = match relief {
+        Relief::DivideBy(_) => start_worry,
+        Relief::Modulo(n) => start_worry % n,
+    };
+    let (mut monkey, mut worry, mut round) = (start_monkey, start_worry, 0u64);
+    let mut skipped = false;
+    while round < num_rounds {
+        if !skipped {
+            if let Some(&loop_start) = seen.get(&(monkey, worry)) {
+                // Rounds always move forwards in a loop, since an item can only
+                // go back to an earlier monkey by waiting for the next round.
+                let loop_rounds = round - visits[loop_start].1;
+                let num_loops = (num_rounds - round) / loop_rounds;
+                for (visited, _) in &visits[loop_start..] {
+                    times_inspected[*visited] += num_loops;
+                }
+                round += num_loops * loop_rounds;
+                skipped = true;
+                continue;
+            }
+            seen.insert((monkey, worry), visits.len());
+            visits.push((monkey, round));
+        }
+
+        let current = &monkeys[monkey];
+        times_inspected[monkey] += 1;
+        let new_worry = match relief {
+            Relief::DivideBy(n) => current.operation.eval(worry).map(|worry| worry / n),
+            Relief::Modulo(n) => current.operation.eval_mod(worry, n),
+        };
+        worry = new_worry.map_err(|e| {
+            format!(
+                "monkey {} with worry level {}: {}",
+                current.monkey_num, worry, e
+            )
+        })?;
+
+        let next = if worry.is_multiple_of(current.divisible_by) {
+            current.true_to
+        } else {
+            current.false_to
+        };
+        // Monkeys take turns in order, so an item thrown to a monkey which
+        // already went this round waits until the next one.
+        if next <= monkey {
+            round += 1;
+        }
+        monkey = next;
+    }
+    Ok(())
+}
+
+// Parse the monkeys using a pattern for each paragraph.
+pub fn input_to_monkeys(input: &str) -> ParseResult<Vec<Monkey>> {
+    let monkey_pattern = Pattern::new(
+        "Monkey {monkey_num}:
+        Starting items: {items}
+        Operation: new = {operation}
+        Test: divisible by {divisible_by}
+          If true: throw to monkey {true_to}
+          If false: throw to monkey {false_to}",
+    );
+
+    let paragraphs = parsing::paragraphs(input);
+    let monkeys = paragraphs
+        .iter()
+        .map(|paragraph| {
+            let text = paragraph.text();
+            let fields = monkey_pattern.parse(&text, paragraph.line)?;
+
+            // The items and operation are on the second and third lines of the paragraph.
+            Ok(Monkey {
+                items_worry: parsing::ints(fields.str("items")?, paragraph.line + 1)?,
+                monkey_num: fields.get("monkey_num")?,
+                operation: expr::parse(fields.str("operation")?)
+                    .map_err(|e| ParseError::new(paragraph.line + 2, e))?,
+                divisible_by: fields.get("divisible_by")?,
+                true_to: fields.get("true_to")?,
+                false_to: fields.get("false_to")?,
+            })
+        })
+        .collect::<ParseResult<Vec<Monkey>>>()?;
+
+    // Make sure every monkey throws to a monkey that exists.
+    for (i, (monkey, paragraph)) in monkeys.iter().zip(&paragraphs).enumerate() {
+        if monkey.monkey_num != i {
+            return Err(ParseError::new(
+                paragraph.line,
+                format!("expected monkey {}, found {}", i, monkey.monkey_num),
+            ));
+        }
+        if monkey.true_to >= monkeys.len() || monkey.false_to >= monkeys.len() {
+            return Err(ParseError::new(
+                paragraph.line,
+                "throws to a monkey that doesn't exist",
+            ));
+        }
+        if monkey.divisible_by == 0 {
+            return Err(ParseError::new(
+                paragraph.line,
+                "can't test divisibility by zero",
+            ));
+        }
+    }
+    Ok(monkeys)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "Monkey 0:
+  Starting items: 79, 98
+  Operation: new = old * 19
+  Test: divisible by 23
+    If true: throw to monkey 2
+    If false: throw to monkey 3
+
+Monkey 1:
+  Starting items: 54, 65, 75, 74
+  Operation: new = old + 6
+  Test: divisible by 19
+    If true: throw to monkey 2
+    If false: throw to monkey 0
+
+Monkey 2:
+  Starting items: 79, 60, 97
+  Operation: new = old * old
+  Test: divisible by 13
+    If true: throw to monkey 1
+    If false: throw to monkey 3
+
+Monkey 3:
+  Starting items: 74
+  Operation: new = old + 3
+  Test: divisible by 17
+    If true: throw to monkey 0
+    If false: throw to monkey 1";
+
+    // The straightforward version: every monkey takes a turn each round.
+    fn simulate_rounds(monkeys: &[Monkey], relief: Relief, num_rounds: u64) -> Vec<u64> {
+        let mut items: Vec<Vec<u64>> = monkeys.iter().map(|m| m.items_worry.clone()).collect();
+        let mut times_inspected = vec![0; monkeys.len()];
+        for _ in 0..num_rounds {
+            for (i, monkey) in monkeys.iter().enumerate() {
+                for worry in std::mem::take(&mut items[i]) {
+                    times_inspected[i] += 1;
+                    let worry = match relief {
+                        Relief::DivideBy(n) => monkey.operation.eval(worry).unwrap() / n,
+                        Relief::Modulo(n) => monkey.operation.eval_mod(worry, n).unwrap(),
+                    };
+                    let next = if worry % monkey.divisible_by == 0 {
+                        monkey.true_to
+                    } else {
+                        monkey.false_to
+                    };
+                    items[next].push(worry);
+                }
+            }
+        }
+        times_inspected
+    }
+
+    #[test]
+    fn test_simulate() {
+        let monkeys = input_to_monkeys(EXAMPLE).unwrap();
+        let relief = Relief::Modulo(23 * 19 * 13 * 17);
+
+        assert_eq!(
+            simulate(&monkeys, Relief::DivideBy(3), 20).unwrap(),
+            vec![101, 95, 7, 105]
+        );
+        assert_eq!(
+            simulate(&monkeys, relief, 10000).unwrap(),
+            vec![52166, 47830, 1938, 52013]
+        );
+        // Skipping ahead through loops has to land on the same counts.
+        for num_rounds in [1, 7, 100, 1234] {
+            assert_eq!(
+                simulate(&monkeys, relief, num_rounds).unwrap(),
+                simulate_rounds(&monkeys, relief, num_rounds)
+            );
+        }
+    }
+
+    #[test]
+    fn test_subtracting_monkey() {
+        let monkeys = input_to_monkeys(
+            "Monkey 0:
+  Starting items: 100, 57, 9
+  Operation: new = old * 2 - 5
+  Test: divisible by 3
+    If true: throw to monkey 1
+    If false: throw to monkey 0
+
+Monkey 1:
+  Starting items: 7
+  Operation: new = old - 1
+  Test: divisible by 2
+    If true: throw to monkey 0
+    If false: throw to monkey 1",
+        )
+        .unwrap();
+        // Worry levels modulo 6 often drop below 5, but the real ones never
+        // do, so subtracting mustn't fail. Without any relief the real worry
+        // levels are small enough to check against.
+        assert_eq!(
+            simulate(&monkeys, Relief::Modulo(6), 20).unwrap(),
+            simulate(&monkeys, Relief::DivideBy(1), 20).unwrap()
+        );
+        assert_eq!(
+            simulate(&monkeys, Relief::Modulo(6), 1000).unwrap(),
+            simulate_rounds(&monkeys, Relief::Modulo(6), 1000)
+        );
+    }
+
+    #[test]
+    fn test_rounds() {
+        assert_eq!(
+            day_11(EXAMPLE, false, &Args::default()).unwrap(),
+            Answer::from(10605u128)
+        );
+        // Each monkey's count after one round, with the top two multiplied.
+        let one = Args::new(&["--rounds", "1"]);
+        let monkeys = input_to_monkeys(EXAMPLE).unwrap();
+        let mut top = simulate_rounds(&monkeys, Relief::DivideBy(3), 1);
+        top.sort_unstable();
+        assert_eq!(
+            day_11(EXAMPLE, false, &one).unwrap(),
+            Answer::from(top[3] as u128 * top[2] as u128)
+        );
+        let typo = Args::new(&["--rounds", "-1"]);
+        assert!(matches!(
+            day_11(EXAMPLE, false, &typo).unwrap(),
+            Answer::Unsolved(_)
+        ));
+    }
+
+    #[test]
+    fn test_too_few_monkeys() {
+        let one = EXAMPLE
+            .split("\n\n")
+            .next()
+            .unwrap()
+            .replace("monkey 2", "monkey 0");
+        let one = one.replace("monkey 3", "monkey 0");
+        for input in ["", "\n\n", one.as_str()] {
+            for part_two in [false, true] {
+                assert!(matches!(
+                    day_11(input, part_two, &Args::default()).unwrap(),
+                    Answer::Unsolved(_)
+                ));
+            }
+        }
+    }
+
+    #[test]
+    fn test_monkey_order() {
+        let input = EXAMPLE.replacen("Monkey 1:", "Monkey 5:", 1);
+        assert_eq!(input_to_monkeys(&input).unwrap_err().line, 8);
+    }
+}
@@ -1,12 +1,12 @@
-use crate::helpers;
+use crate::answer::Answer;
+use crate::helpers::Args;
+use crate::parsing::{self, ParseError, ParseResult};
 use std::cmp::Ordering;
-use std::collections::{BinaryHeap, HashMap};
+use std::collections::{BinaryHeap, HashMap, VecDeque};
 use std::hash::{Hash, Hasher};
-use std::sync::Arc;
-use std::thread;
 
 #[derive(Debug, Clone, Copy)]
-struct Coord {
+pub struct Coord {
     x: i32,
     y: i32,
     #[allow(dead_code)]
@@ -55,7 +55,7 @@ impl PartialOrd for Coord {
     }
 }
 
-pub fn path_finding(input: &String, part_two: bool) -> String {
+pub fn path_finding(input: &str, part_two: bool, _args: &Args) -> ParseResult<Answer> {
     // let input = "Sabqponm
     //              abcryxxl
     //              accszExk
@@ -64,47 +64,55 @@ pub fn path_finding(input: &String, part_two: bool) -> String {
     //     .to_string();
 
     // Get the coordinate grid!
-    let (raw_map, start_coord, end_coord) = get_map(&input);
-    let shared_map = Arc::new(raw_map);
+    let (map, start_coord, end_coord) = get_map(input)?;
 
-    let shortest_path = if part_two {
-        // For each lowland starting point, find the shortest path to the end.
-
-        // Start by beginning A* concurrently on each input. This is about 4x faster than running synchronously.
-        let handles: Vec<_> = get_coords_of_height(&shared_map, 1)
-            .into_iter()
-            .map(|starting_coord| {
-                let map = Arc::clone(&shared_map);
-                thread::spawn(move || find_node_path(map, starting_coord, end_coord))
-            })
-            .collect();
-
-        // Join the handles and find the shortest result. Note that results of 0
-        // length indicate that no path was found, so we must filter those out.
-        handles
-            .into_iter()
-            .filter_map(|handle| handle.join().unwrap())
-            .min_by_key(|path| path.len())
+    let shortest_steps = if part_two {
+        // Any lowland cell can be the start, so search backwards from the end
+        // until the nearest one turns up.
+        steps_from_nearest_lowland(&map, end_coord)
     } else {
-        find_node_path(Arc::clone(&shared_map), start_coord, end_coord)
-    };
-
-    if let Some(path) = shortest_path {
         // The shortest path is length minus one, because it includes the start and
         // end nodes -- if go from cell one to cell three, you only take two steps
         // (1 to 2, and then 2 to 3) to get there.
-        (path.len() - 1).to_string()
-    } else {
-        "No path found!".to_string()
+        find_node_path(&map, start_coord, end_coord).map(|path| path.len() - 1)
+    };
+
+    match shortest_steps {
+        Some(steps) => Ok(steps.into()),
+        None => Ok(Answer::Unsolved("no path found".to_string())),
+    }
+}
+
+// Finds how many steps the closest lowland cell is from the end, with a
+// breadth first search from the end. Steps are taken backwards, so a cell can
+// be reached from a neighbour at most one height above it.
+fn steps_from_nearest_lowland(map: &[Vec<Coord>], end_coord: Coord) -> Option<usize> {
+    let mut steps: Vec<Vec<Option<usize>>> = map.iter().map(|row| vec![None; row.len()]).collect();
+    let mut pending_nodes = VecDeque::from([end_coord]);
+    steps[end_coord.y as usize][end_coord.x as usize] = Some(0);
+
+    while let Some(current_coord) = pending_nodes.pop_front() {
+        let current_steps = steps[current_coord.y as usize][current_coord.x as usize]?;
+        if current_coord.height == 1 {
+            return Some(current_steps);
+        }
+        for neighbor in get_nearby_coords(map, current_coord)
+            .into_iter()
+            .filter(|coord| coord.height + 1 >= current_coord.height)
+        {
+            let neighbor_steps = &mut steps[neighbor.y as usize][neighbor.x as usize];
+            if neighbor_steps.is_none() {
+                *neighbor_steps = Some(current_steps + 1);
+                pending_nodes.push_back(neighbor);
+            }
+        }
     }
+
+    None
 }
 
 // Finds the shortest path from start_coord to end_coord in the map using A*.
-fn find_node_path(
-    map: Arc<Vec<Vec<Coord>>>,
-    start_coord: Coord,
-    end_coord: Coord,
-) -> Option<Vec<Coord>> {
+fn find_node_path(map: &[Vec<Coord>], start_coord: Coord, end_coord: Coord) -> Option<Vec<Coord>> {
     // Create the A* data structures.
     let mut pending_nodes: BinaryHeap<Coord> = BinaryHeap::new();
     let mut came_from: HashMap<Coord, (Coord, i32)> = HashMap::new(); // Include the cost for this pair.
@@ -122,7 +130,7 @@ fn find_node_path(
         if current_coord.same_location(&end_coord) {
             return Some(reconstruct_path(came_from, current_coord));
         }
-        // println!("\nCurrent node: {:?}", current_coord);
+        // eprintln!("\nCurrent node: {:?}", current_coord);
 
         // If we had previously found a better path to this node, we don't need
         // to process this entry, since it will be processed again in the future.
@@ -137,11 +145,11 @@ fn find_node_path(
 
         // Check each neighboring cell that we can actually step to to see if
         // it might become a better path to the end.
-        for neighbor in get_nearby_coords(&map, current_coord)
+        for neighbor in get_nearby_coords(map, current_coord)
             .into_iter()
             .filter(|coord| current_coord.height + 1 >= coord.height)
         {
-            // println!("  Neighbor: {:?}", neighbor);
+            // eprintln!("  Neighbor: {:?}", neighbor);
             // The distance from start to the neighbor through the current node.
             // The distance between nodes is always 1 in this set.
             let new_dist_from_start = current_coord.dist_from_start.unwrap_or(0) + 1;
@@ -149,8 +157,7 @@ fn find_node_path(
 
             // If the path to the neighbor via current is better than the previous
             // best distance to neighbor, let's change the route to use current!
-            if best_dist_from_start == None || new_dist_from_start < *best_dist_from_start.unwrap()
-            {
+            if best_dist_from_start.is_none_or(|best| new_dist_from_start < *best) {
                 came_from.insert(neighbor, (current_coord, new_dist_from_start));
                 score_from_start.insert(neighbor, new_dist_from_start);
 
@@ -166,9 +173,8 @@ fn find_node_path(
                 });
             }
         }
-        // println!("  Next node: {:?}", pending_nodes.peek());
-        // println!("  Pending nodes: {:?}", pending_nodes.len());
-        // helpers::pause();
+        // eprintln!("  Next node: {:?}", pending_nodes.peek());
+        // eprintln!("  Pending nodes: {:?}", pending_nodes.len());
     }
 
     // No path was found :(
@@ -188,30 +194,22 @@ fn reconstruct_path(came_from: HashMap<Coord, (Coord, i32)>, current: Coord) ->
     total_path.into_iter().rev().collect()
 }
 
-// Returns all coordinates of the given height in the map.
-fn get_coords_of_height(map: &Vec<Vec<Coord>>, height: i32) -> Vec<Coord> {
-    map.iter()
-        .flat_map(|row| row.iter().filter(|coord| coord.height == height))
-        .cloned()
-        .collect()
-}
-
 // Our heuristic for A* is the naive number of steps to get to the end, not taking into account the height.
 fn distance_heuristic(coord: Coord, end_coord: Coord) -> i32 {
     (coord.x - end_coord.x).abs() + (coord.y - end_coord.y).abs()
 }
 
 // Gets nearby coordinates, making sure we don't index outside of the map.
-fn get_nearby_coords(map: &Vec<Vec<Coord>>, coord: Coord) -> Vec<Coord> {
+fn get_nearby_coords(map: &[Vec<Coord>], coord: Coord) -> Vec<Coord> {
     let mut coords = vec![];
 
-    if coord.x - 1 >= 0 {
+    if coord.x > 0 {
         coords.push(map[coord.y as usize][coord.x as usize - 1]);
     }
     if coord.x + 1 < map[0].len() as i32 {
         coords.push(map[coord.y as usize][coord.x as usize + 1]);
     }
-    if coord.y - 1 >= 0 {
+    if coord.y > 0 {
         coords.push(map[coord.y as usize - 1][coord.x as usize]);
     }
     if coord.y + 1 < map.len() as i32 {
@@ -222,46 +220,115 @@ fn get_nearby_coords(map: &Vec<Vec<Coord>>, coord: Coord) -> Vec<Coord> {
 }
 
 // Translates the input into a 2D array of coordinates, and finds the start and end coordinates.
-fn get_map(input: &String) -> (Vec<Vec<Coord>>, Coord, Coord) {
+pub fn get_map(input: &str) -> ParseResult<(Vec<Vec<Coord>>, Coord, Coord)> {
     let mut start_coord = None;
     let mut end_coord = None;
-    let map = input
-        .lines()
-        .enumerate()
-        .map(|(line_num, line)| {
-            line.trim()
-                .chars()
-                .enumerate()
-                .map(|(c_num, c)| match c {
-                    'E' | 'S' => {
-                        let coord = Coord {
-                            x: c_num as i32,
-                            y: line_num as i32,
-                            height: if c == 'E' { 26 } else { 1 },
-                            original_val: c,
-                            dist_from_start: if c == 'E' { None } else { Some(0) },
-                            maybe_total_dist: None,
-                        };
-                        if c == 'E' {
-                            end_coord = Some(coord);
-                        } else {
-                            start_coord = Some(coord);
-                        }
-                        coord
-                    }
-                    c => Coord {
-                        x: c_num as i32,
-                        y: line_num as i32,
-                        original_val: c,
-                        height: c as i32 - 96,
-                        dist_from_start: None,
-                        maybe_total_dist: None,
-                    },
-                })
-                .collect()
-        })
-        .collect();
+    let mut map = Vec::new();
 
-    // It's ok to panic -- if there is no start or end coord, something bad happened.
-    (map, start_coord.unwrap(), end_coord.unwrap())
+    for (line_num, line) in parsing::char_grid(input)?.into_iter().enumerate() {
+        let mut row = Vec::new();
+        for (c_num, c) in line.into_iter().enumerate() {
+            let height = match c {
+                'S' => 1,
+                'E' => 26,
+                'a'..='z' => c as i32 - 96,
+                _ => {
+                    return Err(ParseError::new(
+                        line_num + 1,
+                        format!("invalid height '{}'", c),
+                    ))
+                }
+            };
+            let coord = Coord {
+                x: c_num as i32,
+                y: line_num as i32,
+                original_val: c,
+                height,
+                dist_from_start: if c == 'S' { Some(0) } else { None },
+                maybe_total_dist: None,
+            };
+            if c == 'E' {
+                end_coord = Some(coord);
+            } else if c == 'S' {
+                start_coord = Some(coord);
+            }
+            row.push(coord);
+        }
+        map.push(row);
+    }
+
+    match (start_coord, end_coord) {
+        (Some(start), Some(end)) => Ok((map, start, end)),
+        _ => Err(ParseError::new(
+            1,
+            "map needs both a start (S) and an end (E)",
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rng::Rng;
+    use crate::y2022::generate::{self, Size};
+
+    // A path has to go from start to end one step at a time, never climbing
+    // more than one height at once.
+    fn assert_valid_path(path: &[Coord], start: Coord, end: Coord) {
+        assert!(path[0].same_location(&start), "starts at {:?}", path[0]);
+        assert!(path[path.len() - 1].same_location(&end));
+        for pair in path.windows(2) {
+            let (from, to) = (pair[0], pair[1]);
+            assert_eq!((from.x - to.x).abs() + (from.y - to.y).abs(), 1);
+            assert!(to.height <= from.height + 1, "{:?} to {:?}", from, to);
+        }
+    }
+
+    // Returns all coordinates of the given height in the map.
+    fn get_coords_of_height(map: &[Vec<Coord>], height: i32) -> Vec<Coord> {
+        map.iter()
+            .flat_map(|row| row.iter().filter(|coord| coord.height == height))
+            .cloned()
+            .collect()
+    }
+
+    #[test]
+    fn test_example() {
+        let (map, start, end) =
+            get_map("Sabqponm\nabcryxxl\naccszExk\nacctuvwj\nabdefghi\n").unwrap();
+        let path = find_node_path(&map, start, end).unwrap();
+        assert_valid_path(&path, start, end);
+        assert_eq!(path.len() - 1, 31);
+        assert_eq!(steps_from_nearest_lowland(&map, end), Some(29));
+    }
+
+    #[test]
+    fn test_paths_are_valid() {
+        for seed in 0..20 {
+            let mut rng = Rng::new(seed);
+            let size = Size {
+                count: 0,
+                width: rng.range(1, 40) as usize,
+                height: rng.range(1, 40) as usize,
+            };
+            let input = generate::generate(12, size, &mut rng).unwrap();
+            let (map, start, end) = get_map(&input).unwrap();
+
+            // Generated maps always have a way up.
+            let path = find_node_path(&map, start, end).expect("no path found");
+            assert_valid_path(&path, start, end);
+
+            // Searching back from the end has to agree with trying every
+            // lowland cell.
+            let nearest = get_coords_of_height(&map, 1)
+                .into_iter()
+                .filter_map(|lowland| {
+                    let path = find_node_path(&map, lowland, end)?;
+                    assert_valid_path(&path, lowland, end);
+                    Some(path.len() - 1)
+                })
+                .min();
+            assert_eq!(steps_from_nearest_lowland(&map, end), nearest);
+        }
+    }
 }
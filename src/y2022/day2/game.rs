@@ -0,0 +1,145 @@
+// Hand games like Rock Paper Scissors, described as data instead of matches.
+// Any game with an odd number of shapes can be made fair by arranging them in
+// a circle, where each shape beats the half of the others just before it:
+// shape i beats shape j if (i - j) mod n is between 1 and (n - 1) / 2.
+//
+// Rock Paper Scissors is [Rock, Paper, Scissors] in that order, and Rock Paper
+// Scissors Lizard Spock is [Rock, Spock, Paper, Lizard, Scissors].
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Outcome {
+    Loss,
+    Draw,
+    Win,
+}
+
+impl Outcome {
+    pub const ALL: [Outcome; 3] = [Outcome::Loss, Outcome::Draw, Outcome::Win];
+}
+
+#[derive(Debug, Clone)]
+pub struct Game {
+    pub shapes: Vec<String>,
+    // Points for playing each shape.
+    pub shape_scores: Vec<i64>,
+    // Points for a loss, a draw and a win, in that order.
+    pub outcome_scores: [i64; 3],
+}
+
+impl Game {
+    pub fn new(
+        shapes: Vec<String>,
+        shape_scores: Vec<i64>,
+        outcome_scores: [i64; 3],
+    ) -> Result<Game, String> {
+        if shapes.len() < 3 || shapes.len().is_multiple_of(2) {
+            return Err(format!(
+                "a fair game needs an odd number of shapes (at least 3), not {}",
+                shapes.len()
+            ));
+        }
+        if shape_scores.len() != shapes.len() {
+            return Err(format!(
+                "there are {} shapes but {} shape scores",
+                shapes.len(),
+                shape_scores.len()
+            ));
+        }
+        Ok(Game {
+            shapes,
+            shape_scores,
+            outcome_scores,
+        })
+    }
+
+    // A game with the puzzle's scoring: 1 point for the first shape, 2 for the
+    // second and so on, plus 0 for a loss, 3 for a draw and 6 for a win.
+    pub fn with_shapes(shapes: &[&str]) -> Result<Game, String> {
+        Game::new(
+            shapes.iter().map(|shape| shape.to_string()).collect(),
+            (1..=shapes.len() as i64).collect(),
+            [0, 3, 6],
+        )
+    }
+
+    pub fn by_name(name: &str) -> Option<Game> {
+        let shapes: &[&str] = match name {
+            "rps" => &["Rock", "Paper", "Scissors"],
+            "rpsls" => &["Rock", "Spock", "Paper", "Lizard", "Scissors"],
+            _ => return None,
+        };
+        Game::with_shapes(shapes).ok()
+    }
+
+    pub fn outcome(&self, me: usize, opponent: usize) -> Outcome {
+        let n = self.shapes.len();
+        match (me + n - opponent) % n {
+            0 => Outcome::Draw,
+            difference if difference <= (n - 1) / 2 => Outcome::Win,
+            _ => Outcome::Loss,
+        }
+    }
+
+    // The shape to play against the opponent's to get an outcome. In games with
+    // more than three shapes there are several, so this picks the one worth
+    // the most points.
+    pub fn choose(&self, opponent: usize, outcome: Outcome) -> usize {
+        (0..self.shapes.len())
+            .filter(|me| self.outcome(*me, opponent) == outcome)
+            .max_by_key(|me| (self.shape_scores[*me], usize::MAX - me))
+            .expect("every outcome is possible with at least 3 shapes")
+    }
+
+    // None if the scores are too big to add up.
+    pub fn score(&self, me: usize, opponent: usize) -> Option<i64> {
+        self.shape_scores[me].checked_add(self.outcome_scores[self.outcome(me, opponent) as usize])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rules() {
+        let rps = Game::by_name("rps").unwrap();
+        let (rock, paper, scissors) = (0, 1, 2);
+        assert_eq!(rps.outcome(paper, rock), Outcome::Win);
+        assert_eq!(rps.outcome(rock, scissors), Outcome::Win);
+        assert_eq!(rps.outcome(rock, paper), Outcome::Loss);
+        assert_eq!(rps.outcome(scissors, scissors), Outcome::Draw);
+        assert_eq!(rps.choose(rock, Outcome::Loss), scissors);
+        assert_eq!(rps.score(paper, rock), Some(8));
+
+        // Every shape beats exactly two others, and choose always gets the
+        // outcome asked for.
+        let rpsls = Game::by_name("rpsls").unwrap();
+        let shape = |name: &str| rpsls.shapes.iter().position(|s| s == name).unwrap();
+        assert_eq!(
+            rpsls.outcome(shape("Spock"), shape("Scissors")),
+            Outcome::Win
+        );
+        assert_eq!(rpsls.outcome(shape("Lizard"), shape("Spock")), Outcome::Win);
+        assert_eq!(rpsls.outcome(shape("Rock"), shape("Lizard")), Outcome::Win);
+        for opponent in 0..5 {
+            let wins = (0..5)
+                .filter(|me| rpsls.outcome(*me, opponent) == Outcome::Win)
+                .count();
+            assert_eq!(wins, 2);
+            for outcome in Outcome::ALL {
+                assert_eq!(
+                    rpsls.outcome(rpsls.choose(opponent, outcome), opponent),
+                    outcome
+                );
+            }
+        }
+
+        assert!(Game::with_shapes(&["Rock", "Paper"]).is_err());
+        assert!(Game::new(
+            vec!["a".into(), "b".into(), "c".into()],
+            vec![1, 2],
+            [0, 3, 6]
+        )
+        .is_err());
+    }
+}
@@ -0,0 +1,308 @@
+use crate::answer::Answer;
+use crate::helpers::Args;
+use crate::parsing::{ParseError, ParseResult, Pattern};
+use game::{Game, Outcome};
+use std::cmp::Reverse;
+
+mod game;
+
+// One line of the strategy guide: the opponent's shape, and which of my
+// symbols the guide says to use (as a position in the list of my symbols).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Round {
+    opponent: usize,
+    column: usize,
+}
+
+// What each of my symbols means. In part one every symbol is a shape to play,
+// and in part two it's the outcome to aim for.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Strategy {
+    Play(Vec<usize>),
+    Aim(Vec<Outcome>),
+}
+
+// Goal: compute my total score in rock paper scissors by assesing the outcome of each round.
+pub fn get_score_from_elf_rps_game(
+    input: &str,
+    part_two: bool,
+    args: &Args,
+) -> ParseResult<Answer> {
+    let game = match game_from_args(args) {
+        Ok(game) => game,
+        Err(e) => return Ok(Answer::Unsolved(e)),
+    };
+
+    // The opponent's symbols are the shapes in order, A, B, C and so on. My
+    // symbols are the shapes too in part one (ending at Z, so X, Y and Z for
+    // three shapes), and in part two X, Y and Z are a loss, draw and win.
+    // Either can be changed with "--opponent-symbols ABC" or "--my-symbols XYZ".
+    let choices = if part_two { 3 } else { game.shapes.len() };
+    let opponent_symbols = symbols_arg(args, "--opponent-symbols")
+        .unwrap_or_else(|| ('A'..='Z').take(game.shapes.len()).collect());
+    let my_symbols = symbols_arg(args, "--my-symbols").unwrap_or_else(|| {
+        let letters: Vec<char> = ('A'..='Z').collect();
+        letters[26 - choices.min(26)..].to_vec()
+    });
+    if let Err(e) = check_symbols(&opponent_symbols, game.shapes.len(), "opponent")
+        .and_then(|_| check_symbols(&my_symbols, choices, "my"))
+    {
+        return Ok(Answer::Unsolved(e));
+    }
+
+    let rounds = parse_guide(input, &opponent_symbols, &my_symbols)?;
+    let strategy = if part_two {
+        Strategy::Aim(Outcome::ALL[..my_symbols.len()].to_vec())
+    } else {
+        Strategy::Play((0..my_symbols.len()).collect())
+    };
+
+    // "--analyse" tries every other meaning the guide's symbols could have.
+    if args.has("--analyse") {
+        analyse(&game, &rounds, &my_symbols, part_two);
+    }
+
+    Ok(match total_score(&game, &rounds, &strategy) {
+        Some(score) => score.into(),
+        None => Answer::Unsolved("the score is too big to add up".to_string()),
+    })
+}
+
+// Builds the game from "--game rps|rpsls" (or "--shapes Rock,Paper,Scissors"
+// for anything else, listed in their circle order), with the scores
+// optionally replaced by "--shape-scores 1,2,3" and "--outcome-scores 0,3,6".
+fn game_from_args(args: &Args) -> Result<Game, String> {
+    let mut game = match (args.value("--shapes"), args.value("--game")) {
+        (Some(shapes), _) => {
+            Game::with_shapes(&shapes.split(',').map(str::trim).collect::<Vec<_>>())?
+        }
+        (None, Some(name)) => Game::by_name(name)
+            .ok_or_else(|| format!("unknown game '{}', use rps or rpsls", name))?,
+        (None, None) => Game::by_name("rps").unwrap(),
+    };
+    if let Some(scores) = args.value("--shape-scores") {
+        let scores = parse_scores(scores)?;
+        game = Game::new(game.shapes, scores, game.outcome_scores)?;
+    }
+    if let Some(scores) = args.value("--outcome-scores") {
+        game.outcome_scores = parse_scores(scores)?
+            .try_into()
+            .map_err(|_| "there must be 3 outcome scores: loss, draw and win".to_string())?;
+    }
+    Ok(game)
+}
+
+fn parse_scores(scores: &str) -> Result<Vec<i64>, String> {
+    scores
+        .split(',')
+        .map(|score| {
+            score
+                .trim()
+                .parse()
+                .map_err(|_| format!("invalid score '{}'", score))
+        })
+        .collect()
+}
+
+fn symbols_arg(args: &Args, arg: &str) -> Option<Vec<char>> {
+    args.value(arg).map(|symbols| symbols.chars().collect())
+}
+
+fn check_symbols(symbols: &[char], most: usize, whose: &str) -> Result<(), String> {
+    if symbols.is_empty() || symbols.len() > most {
+        return Err(format!(
+            "{} symbols must number between 1 and {}, not {}",
+            whose,
+            most,
+            symbols.len()
+        ));
+    }
+    for (i, symbol) in symbols.iter().enumerate() {
+        if symbols[..i].contains(symbol) {
+            return Err(format!("{} symbol '{}' is used twice", whose, symbol));
+        }
+    }
+    Ok(())
+}
+
+pub fn parse_guide(
+    input: &str,
+    opponent_symbols: &[char],
+    my_symbols: &[char],
+) -> ParseResult<Vec<Round>> {
+    let round_pattern = Pattern::new("{opponent} {me}");
+    let symbol = |text: &str, symbols: &[char], line: usize, what: &str| {
+        let mut chars = text.chars();
+        match (chars.next(), chars.next()) {
+            (Some(c), None) => symbols.iter().position(|symbol| *symbol == c),
+            _ => None,
+        }
+        .ok_or_else(|| ParseError::new(line, format!("invalid {} '{}'", what, text)))
+    };
+
+    let mut rounds = Vec::new();
+    for (i, line) in input.lines().enumerate() {
+        // Skip empty lines.
+        if line.trim().is_empty() {
+            continue;
+        }
+        let choices = round_pattern.parse(line, i + 1)?;
+        rounds.push(Round {
+            opponent: symbol(
+                choices.str("opponent")?,
+                opponent_symbols,
+                i + 1,
+                "opponent choice",
+            )?,
+            column: symbol(choices.str("me")?, my_symbols, i + 1, "choice")?,
+        });
+    }
+    Ok(rounds)
+}
+
+// None if the scores overflow, which only happens with silly "--shape-scores"
+// or "--outcome-scores".
+fn total_score(game: &Game, rounds: &[Round], strategy: &Strategy) -> Option<i64> {
+    rounds.iter().try_fold(0i64, |total, round| {
+        let me = match strategy {
+            Strategy::Play(shapes) => shapes[round.column],
+            Strategy::Aim(outcomes) => game.choose(round.opponent, outcomes[round.column]),
+        };
+        total.checked_add(game.score(me, round.opponent)?)
+    })
+}
+
+// Trying every mapping gets slow quickly, since there are n! of them for n
+// symbols. 8! is about 40 thousand.
+const MAX_MAPPINGS: usize = 40320;
+
+// Scores the guide under every way of giving my symbols different meanings,
+// and prints them from best to worst.
+fn analyse(game: &Game, rounds: &[Round], my_symbols: &[char], part_two: bool) {
+    let choices = if part_two { 3 } else { game.shapes.len() };
+    match count_assignments(my_symbols.len(), choices) {
+        Some(count) if count <= MAX_MAPPINGS => (),
+        _ => {
+            eprintln!(
+                "Too many ways to map {} symbols to check them all (the limit is {}).",
+                my_symbols.len(),
+                MAX_MAPPINGS
+            );
+            return;
+        }
+    }
+    let mut results: Vec<(Option<i64>, String)> = assignments(my_symbols.len(), choices)
+        .into_iter()
+        .map(|assignment| {
+            let strategy = if part_two {
+                Strategy::Aim(assignment.iter().map(|i| Outcome::ALL[*i]).collect())
+            } else {
+                Strategy::Play(assignment.clone())
+            };
+            let meanings: Vec<String> = my_symbols
+                .iter()
+                .zip(&assignment)
+                .map(|(symbol, i)| match &strategy {
+                    Strategy::Play(_) => format!("{}={}", symbol, game.shapes[*i]),
+                    Strategy::Aim(_) => format!("{}={:?}", symbol, Outcome::ALL[*i]),
+                })
+                .collect();
+            (total_score(game, rounds, &strategy), meanings.join(" "))
+        })
+        .collect();
+    // Best first, keeping the order they were tried in for ties. Scores which
+    // overflowed go last.
+    results.sort_by_key(|(score, _)| Reverse(*score));
+    let show = |score: &Option<i64>| match score {
+        Some(score) => score.to_string(),
+        None => "overflow".to_string(),
+    };
+
+    // Only the top few are listed, since a big game has a lot of mappings.
+    let shown = results.len().min(10);
+    eprintln!("Scores for each of the {} mappings:", results.len());
+    for (score, meanings) in &results[..shown] {
+        eprintln!("  {:>8}  {}", show(score), meanings);
+    }
+    if shown < results.len() {
+        eprintln!("  ... and {} more", results.len() - shown);
+    }
+    eprintln!("Best mapping: {} ({})", results[0].1, show(&results[0].0));
+}
+
+// How many assignments there are, or None if that doesn't fit in a usize.
+fn count_assignments(count: usize, choices: usize) -> Option<usize> {
+    if count > choices {
+        return Some(0);
+    }
+    (choices - count + 1..=choices).try_fold(1usize, |total, n| total.checked_mul(n))
+}
+
+// Every way of giving each of `count` things a different one of `choices`
+// values, e.g. the 6 orderings of 0, 1 and 2 for 3 and 3.
+fn assignments(count: usize, choices: usize) -> Vec<Vec<usize>> {
+    if count == 0 {
+        return vec![Vec::new()];
+    }
+    let mut all = Vec::new();
+    for rest in assignments(count - 1, choices) {
+        for choice in (0..choices).filter(|choice| !rest.contains(choice)) {
+            let mut assignment = rest.clone();
+            assignment.push(choice);
+            all.push(assignment);
+        }
+    }
+    all
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_strategies() {
+        let game = Game::by_name("rps").unwrap();
+        let rounds = parse_guide("A Y\nB X\n\nC Z\n", &['A', 'B', 'C'], &['X', 'Y', 'Z']).unwrap();
+        assert_eq!(
+            total_score(&game, &rounds, &Strategy::Play(vec![0, 1, 2])),
+            Some(15)
+        );
+        let aim = Strategy::Aim(Outcome::ALL.to_vec());
+        assert_eq!(total_score(&game, &rounds, &aim), Some(12));
+        let huge = Game::new(game.shapes.clone(), vec![i64::MAX; 3], [0; 3]).unwrap();
+        assert_eq!(total_score(&huge, &rounds, &aim), None);
+
+        assert_eq!(assignments(3, 3).len(), 6);
+        assert_eq!(assignments(2, 5).len(), 20);
+        assert_eq!(assignments(2, 2), vec![vec![0, 1], vec![1, 0]]);
+        assert_eq!(count_assignments(2, 5), Some(20));
+        assert_eq!(count_assignments(4, 3), Some(0));
+        assert_eq!(count_assignments(8, 8), Some(MAX_MAPPINGS));
+        assert_eq!(count_assignments(40, 40), None);
+
+        let bad = parse_guide("A X\nD X", &['A', 'B', 'C'], &['X']).unwrap_err();
+        assert_eq!(bad.line, 2);
+        assert_eq!(bad.message, "invalid opponent choice 'D'");
+    }
+
+    #[test]
+    fn test_options() {
+        let game = game_from_args(&Args::new(&[
+            "--game",
+            "rpsls",
+            "--outcome-scores",
+            "1,2,3",
+        ]));
+        let game = game.unwrap();
+        assert_eq!(game.shapes.len(), 5);
+        assert_eq!(game.outcome_scores, [1, 2, 3]);
+        assert!(game_from_args(&Args::new(&["--game", "chess"])).is_err());
+        assert!(game_from_args(&Args::new(&["--shape-scores", "1,x,3"])).is_err());
+
+        let args = Args::new(&["--my-symbols", "RPS"]);
+        assert_eq!(
+            get_score_from_elf_rps_game("A P\nB R\nC S", false, &args).unwrap(),
+            get_score_from_elf_rps_game("A Y\nB X\nC Z", false, &Args::default()).unwrap()
+        );
+    }
+}
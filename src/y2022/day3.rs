@@ -1,66 +1,124 @@
-use std::collections::HashMap;
-use std::collections::HashSet;
+use crate::answer::Answer;
+use crate::helpers::Args;
+use crate::parsing::{ParseError, ParseResult};
+
+pub fn rucksack_calculations(input: &str, part_two: bool, args: &Args) -> ParseResult<Answer> {
+    let rucksacks = parse_rucksacks(input)?;
 
-pub fn rucksack_calculations(input: &String, part_two: bool) -> String {
     if part_two {
-        get_item_badge_priorities(input)
+        // Elves come in groups of three, or any other size with "--group-size N".
+        let group_size = match args.parsed::<usize>("--group-size") {
+            Ok(group_size) => group_size.unwrap_or(3),
+            Err(e) => return Ok(Answer::Unsolved(e)),
+        };
+        if group_size == 0 {
+            return Ok(Answer::Unsolved(
+                "a group needs at least one elf".to_string(),
+            ));
+        }
+        Ok(get_item_badge_priorities(&rucksacks, group_size)?.into())
     } else {
-        get_split_item_priorities(input)
+        Ok(get_split_item_priorities(&rucksacks).into())
     }
 }
 
-// Gets the score of the badges (and their priorities) for each elf group.
-fn get_item_badge_priorities(input: &String) -> String {
-    // Separate the input into groups of three elves.
-    let all_elves = input.lines().collect::<Vec<_>>();
-    let elf_groups = all_elves.chunks(3);
-
-    elf_groups
-        .fold(0, |priority_sums, group| {
-            // We want to find the single item shared between all three elves. We
-            // can do that by intersecting A & B, and then intersecting the result
-            // with C.
-
-            // Do some weird conversions from a vector of chars to String (and then &str).
-            let first_two = intersection(group[0], group[1])
-                .into_iter()
-                .collect::<String>();
-
-            // The intersection of (the intersection of the first two) and three is the intersection of all three.
-            let chars_in_all = intersection(first_two.as_str(), group[2]);
-
-            // Finally, we score the characters.
-            priority_sums + score_chars(chars_in_all)
-        })
-        .to_string()
+// A set of item types, one bit per type. Bit 0 is unused, so each item's bit
+// is its priority (a-z are 1-26, A-Z are 27-52).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct ItemSet(u64);
+
+impl ItemSet {
+    const ALL: ItemSet = ItemSet(((1 << 52) - 1) << 1);
+
+    fn from_items(items: &str) -> ItemSet {
+        ItemSet(
+            items
+                .chars()
+                .fold(0, |bits, c| bits | 1 << char_to_score(c)),
+        )
+    }
+
+    fn intersection(self, other: ItemSet) -> ItemSet {
+        ItemSet(self.0 & other.0)
+    }
+
+    // The sum of the priorities of every item in the set.
+    fn priorities(self) -> u64 {
+        (1..=52).filter(|bit| self.0 & 1 << bit != 0).sum()
+    }
 }
 
-// Gets the score of the items split into both rucksacks.
-fn get_split_item_priorities(input: &String) -> String {
-    // Each new line of input represents a rucksack.
-    input
-        .lines()
-        .fold(0, |priority_sums, items| {
-            if items.is_empty() {
-                return priority_sums;
-            }
-            // One half of the line is one compartment, the other half is the other compartment.
-            let (compartment_a, compartment_b) = items.split_at(items.len() / 2);
-
-            // Find each character shared in both rucksack compartments.
-            let chars_in_both = intersection(compartment_a, compartment_b);
-
-            println!("Shared Items in strs: {}-{}", compartment_a, compartment_b);
-
-            // Sum the "priorities" of those characters.
-            priority_sums + score_chars(chars_in_both)
-        })
-        .to_string()
+// A rucksack with the line it came from, so groups can report where they are.
+#[derive(Debug)]
+pub struct Rucksack<'a> {
+    line: usize,
+    items: &'a str,
 }
 
-// Basic wrapper to score a vector of characters, which is the output of the intersection function.
-fn score_chars(c: Vec<char>) -> u32 {
-    c.iter().fold(0, |score, c| score + char_to_score(*c))
+// Every non-blank line is a rucksack. Each one has two compartments of the same
+// size, and only holds letters.
+pub fn parse_rucksacks(input: &str) -> ParseResult<Vec<Rucksack<'_>>> {
+    let mut rucksacks = Vec::new();
+    for (i, items) in input.lines().enumerate() {
+        let (line, items) = (i + 1, items.trim());
+        if items.is_empty() {
+            continue;
+        }
+        if let Some(bad) = items.chars().find(|c| !c.is_ascii_alphabetic()) {
+            return Err(ParseError::new(
+                line,
+                format!("'{}' is not an item type, only letters are", bad),
+            ));
+        }
+        if items.len() % 2 != 0 {
+            return Err(ParseError::new(
+                line,
+                format!(
+                    "a rucksack with {} items can't be split into two equal compartments",
+                    items.len()
+                ),
+            ));
+        }
+        rucksacks.push(Rucksack { line, items });
+    }
+    Ok(rucksacks)
+}
+
+// Gets the score of the badges (and their priorities) for each elf group. The
+// badge is whatever item every elf in the group is carrying. Sums are u64s,
+// since a big enough input adds up to more than a u32 holds.
+fn get_item_badge_priorities(rucksacks: &[Rucksack], group_size: usize) -> ParseResult<u64> {
+    let mut priority_sums = 0u64;
+    for group in rucksacks.chunks(group_size) {
+        if group.len() < group_size {
+            return Err(ParseError::new(
+                group[0].line,
+                format!(
+                    "the last group only has {} elves, not {}",
+                    group.len(),
+                    group_size
+                ),
+            ));
+        }
+        let badges = group.iter().fold(ItemSet::ALL, |shared, rucksack| {
+            shared.intersection(ItemSet::from_items(rucksack.items))
+        });
+        priority_sums += badges.priorities();
+    }
+    Ok(priority_sums)
+}
+
+// Gets the score of the items split into both rucksacks.
+fn get_split_item_priorities(rucksacks: &[Rucksack]) -> u64 {
+    rucksacks.iter().fold(0u64, |priority_sums, rucksack| {
+        // One half of the line is one compartment, the other half is the other compartment.
+        let (compartment_a, compartment_b) = rucksack.items.split_at(rucksack.items.len() / 2);
+
+        // Find each item type in both compartments, and sum their "priorities".
+        let in_both =
+            ItemSet::from_items(compartment_a).intersection(ItemSet::from_items(compartment_b));
+        priority_sums + in_both.priorities()
+    })
 }
 
 // Use Ascii trickery to get the score of a character.
@@ -74,26 +132,43 @@ fn char_to_score(c: char) -> u32 {
     }
 }
 
-// Returns a list of characters contained in both strings.
-fn intersection(a: &str, b: &str) -> Vec<char> {
-    // Map of characters to number of occurrences in the string.
-    let mut char_map = HashMap::new();
-
-    // Add everything in a to the map.
-    for c in a.chars() {
-        char_map
-            .entry(c)
-            .and_modify(|occurrences| *occurrences += 1)
-            .or_insert(1);
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "vJrwpWtwJgWrhcsFMMfFFhFp
+jqHRNqRjqzjGDLGLrsFMfFZSrLrFZsSL
+PmmdzqPrVvPwwTWBwg
+wMqvLMZHhHMvwLHjbvcjnnSBnvTQFn
+ttgJtRGJQctTZtZT
+CrZsJsPPZsGzwwsLwLmpwMDw";
+
+    #[test]
+    fn test_item_set() {
+        assert_eq!(ItemSet::from_items("aZ"), ItemSet(1 << 1 | 1 << 52));
+        assert_eq!(ItemSet::ALL.priorities(), (1..=52).sum::<u64>());
+        assert_eq!(ItemSet::from_items("pLp").priorities(), 16 + 38);
+        assert_eq!(
+            ItemSet::from_items("abc").intersection(ItemSet::from_items("cbd")),
+            ItemSet::from_items("bc")
+        );
     }
 
-    // Create a set of characters that are in both strings.
-    let mut chars_in_both = HashSet::new();
-    for c in b.chars() {
-        if char_map.contains_key(&c) {
-            chars_in_both.insert(c);
-        }
+    #[test]
+    fn test_priorities() {
+        let rucksacks = parse_rucksacks(EXAMPLE).unwrap();
+        assert_eq!(get_split_item_priorities(&rucksacks), 157);
+        assert_eq!(get_item_badge_priorities(&rucksacks, 3).unwrap(), 70);
+        // Nothing is in all six rucksacks, and four leaves an incomplete group.
+        assert_eq!(get_item_badge_priorities(&rucksacks, 6).unwrap(), 0);
+        assert_eq!(
+            get_item_badge_priorities(&rucksacks, 4).unwrap_err().line,
+            5
+        );
+
+        assert_eq!(parse_rucksacks("abcd\nabc\n").unwrap_err().line, 2);
+        let bad = parse_rucksacks("ab\n\nab1c").unwrap_err();
+        assert_eq!(bad.line, 3);
+        assert_eq!(bad.message, "'1' is not an item type, only letters are");
     }
-
-    return chars_in_both.into_iter().collect();
 }
@@ -1,65 +1,93 @@
-use std::ops::Range;
+use crate::answer::Answer;
+use crate::helpers::Args;
+use crate::interval::{Interval, IntervalSet};
+use crate::parsing::{ParseError, ParseResult, Pattern};
 
-pub fn assignment_checker(input: &String, part_two: bool) -> String {
-    input
-        .lines()
-        .fold(0, |num_overlapping, elf_pair| {
-            // Each line contains assignments for a pair of elves. Each of those
-            // assignments represents a range of numbers.
-            let (elf_a_range, elf_b_range) = input_to_range(elf_pair);
+pub fn assignment_checker(input: &str, part_two: bool, args: &Args) -> ParseResult<Answer> {
+    let pair_pattern = Pattern::new("{a_start}-{a_end},{b_start}-{b_end}");
+    let mut num_overlapping = 0;
+    let mut covered: IntervalSet<u32> = IntervalSet::new();
 
-            // Determine if the current pair of ranges overlaps according to the challenge rules.
-            let has_overlap: bool = if part_two {
-                range_overlap(&elf_a_range, &elf_b_range)
-            } else {
-                range_contains(&elf_a_range, &elf_b_range)
-            };
+    for (i, elf_pair) in input.lines().enumerate() {
+        if elf_pair.trim().is_empty() {
+            continue;
+        }
+        // Each line contains assignments for a pair of elves. Each of those
+        // assignments represents a range of sections.
+        let (elf_a, elf_b) = parse_pair(&pair_pattern, elf_pair, i + 1)?;
+        covered.insert(elf_a);
+        covered.insert(elf_b);
 
-            return num_overlapping + has_overlap as u32;
-        })
-        .to_string()
-}
-
-// Extends the range to allow calculations of "overlap" and "range contains"
-pub trait RangeExt<T> {
-    fn contains_range(&self, other: &Range<T>) -> bool;
-    fn overlaps_range(&self, other: &Range<u32>) -> bool;
-}
+        // Determine if the current pair of ranges overlaps according to the challenge rules.
+        let has_overlap: bool = if part_two {
+            elf_a.overlaps(&elf_b)
+        } else {
+            fully_contains(&elf_a, &elf_b)
+        };
 
-impl RangeExt<u32> for Range<u32> {
-    // True if the other range is entirely within the bounds of this range.
-    fn contains_range(&self, other: &Range<u32>) -> bool {
-        // Start and end are both beyond the bounds of the other range.
-        self.start <= other.start && self.end >= other.end
+        num_overlapping += has_overlap as u32;
     }
 
-    // True if either the start or end is within the bounds of the other range.
-    // E.g. true if there is a partial range overlap.
-    fn overlaps_range(&self, other: &Range<u32>) -> bool {
-        (other.start <= self.start && self.start <= other.end) // Start within bounds.
-            || (other.start <= self.end && self.end <= other.end) // End within bounds.
+    // "--gaps" reports which sections no elf has been assigned.
+    if args.has("--gaps") {
+        eprintln!(
+            "{} sections are assigned, and these are left out: {}",
+            covered.len(),
+            covered.gaps()
+        );
     }
+
+    Ok(num_overlapping.into())
 }
 
-fn range_overlap(range_a: &Range<u32>, range_b: &Range<u32>) -> bool {
-    range_a.overlaps_range(range_b) || range_b.overlaps_range(range_a)
+// True if either assignment is entirely within the other.
+fn fully_contains(a: &Interval<u32>, b: &Interval<u32>) -> bool {
+    a.contains_interval(b) || b.contains_interval(a)
 }
 
-fn range_contains(range_a: &Range<u32>, range_b: &Range<u32>) -> bool {
-    range_a.contains_range(range_b) || range_b.contains_range(range_a)
+// Converts a pair of assignments (e.g. "1-3,2-4") into intervals.
+pub fn parse_pair(
+    pair_pattern: &Pattern,
+    range_pair: &str,
+    line: usize,
+) -> ParseResult<(Interval<u32>, Interval<u32>)> {
+    let fields = pair_pattern.parse(range_pair, line)?;
+    let interval = |start: &str, end: &str| -> ParseResult<Interval<u32>> {
+        let (start, end) = (fields.get::<u32>(start)?, fields.get::<u32>(end)?);
+        Interval::new(start, end).ok_or_else(|| {
+            ParseError::new(
+                line,
+                format!("assignment {}-{} ends before it starts", start, end),
+            )
+        })
+    };
+    Ok((interval("a_start", "a_end")?, interval("b_start", "b_end")?))
 }
 
-// Converts a range string (e.g. "1-3") into an actual range type.
-fn input_to_range(range_pair: &str) -> (Range<u32>, Range<u32>) {
-    // Tuple where each element is the elf range.
-    let (elf_a, elf_b) = range_pair.rsplit_once(',').unwrap();
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rng::Rng;
+    use crate::y2022::generate::{self, Size};
 
-    // Tuple where each element is the start and end of the range.
-    let (a_start, a_end) = elf_a.rsplit_once('-').unwrap();
-    let (b_start, b_end) = elf_b.rsplit_once('-').unwrap();
+    #[test]
+    fn test_overlap_properties() {
+        let pair_pattern = Pattern::new("{a_start}-{a_end},{b_start}-{b_end}");
+        for seed in 0..10 {
+            let input = generate::generate(4, Size::new(200), &mut Rng::new(seed)).unwrap();
+            for (i, line) in input.lines().enumerate() {
+                let (a, b) = parse_pair(&pair_pattern, line, i + 1).unwrap();
+                assert_eq!(a.overlaps(&b), b.overlaps(&a), "{}", line);
+                assert_eq!(fully_contains(&a, &b), fully_contains(&b, &a), "{}", line);
+                assert!(!fully_contains(&a, &b) || a.overlaps(&b), "{}", line);
+                // Overlapping means at least one section is in both.
+                let shared = (a.start..=a.end).any(|section| b.contains(section));
+                assert_eq!(a.overlaps(&b), shared, "{}", line);
+            }
+        }
 
-    // Convert the strings to numbers and then to a range:
-    let a_range = a_start.parse::<u32>().unwrap()..a_end.parse::<u32>().unwrap();
-    let b_range = b_start.parse::<u32>().unwrap()..b_end.parse::<u32>().unwrap();
-    (a_range, b_range)
+        let backwards = parse_pair(&pair_pattern, "2-4,6-5", 3).unwrap_err();
+        assert_eq!(backwards.line, 3);
+        assert_eq!(backwards.message, "assignment 6-5 ends before it starts");
+    }
 }
@@ -0,0 +1,178 @@
+// Cranes move crates between stacks. Every crane lifts some number of crates
+// off the top of one stack and puts them on top of another, but models differ
+// in what order the crates land in. To add a new model, implement Crane and
+// add it to `by_name`.
+
+pub trait Crane {
+    fn name(&self) -> String;
+
+    // Given the crates taken off the top of a stack (bottom to top), returns
+    // them in the order they end up on the destination (bottom to top).
+    fn rearrange(&self, lifted: Vec<char>) -> Vec<char>;
+}
+
+// Moves crates one at a time, so their order is reversed.
+pub struct CrateMover9000;
+
+impl Crane for CrateMover9000 {
+    fn name(&self) -> String {
+        "CrateMover 9000".to_string()
+    }
+
+    fn rearrange(&self, mut lifted: Vec<char>) -> Vec<char> {
+        lifted.reverse();
+        lifted
+    }
+}
+
+// Moves all the crates at once, so their order is kept.
+pub struct CrateMover9001;
+
+impl Crane for CrateMover9001 {
+    fn name(&self) -> String {
+        "CrateMover 9001".to_string()
+    }
+
+    fn rearrange(&self, lifted: Vec<char>) -> Vec<char> {
+        lifted
+    }
+}
+
+// Lifts up to `capacity` crates at a time, keeping the order within each lift.
+// A capacity of 1 behaves like the 9000.
+pub struct LimitedCrane {
+    pub capacity: usize,
+}
+
+impl Crane for LimitedCrane {
+    fn name(&self) -> String {
+        format!("crane lifting {} at a time", self.capacity)
+    }
+
+    fn rearrange(&self, lifted: Vec<char>) -> Vec<char> {
+        // The first lift takes the top chunk, and ends up at the bottom.
+        let mut landed = Vec::with_capacity(lifted.len());
+        let mut remaining = lifted.as_slice();
+        while !remaining.is_empty() {
+            let split = remaining.len().saturating_sub(self.capacity);
+            landed.extend_from_slice(&remaining[split..]);
+            remaining = &remaining[..split];
+        }
+        landed
+    }
+}
+
+// Looks up a crane from the command line: "9000", "9001", or "lift:N".
+pub fn by_name(name: &str) -> Option<Box<dyn Crane>> {
+    match name {
+        "9000" => Some(Box::new(CrateMover9000)),
+        "9001" => Some(Box::new(CrateMover9001)),
+        _ => {
+            let capacity = name.strip_prefix("lift:")?.parse::<usize>().ok()?;
+            if capacity == 0 {
+                return None;
+            }
+            Some(Box::new(LimitedCrane { capacity }))
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Move {
+    pub count: usize,
+    // Stack numbers, starting at 1 like the puzzle.
+    pub from: usize,
+    pub to: usize,
+}
+
+// Carries out a move, or explains why it can't be done. Stacks are left
+// untouched if the move is invalid.
+pub fn apply(crane: &dyn Crane, stacks: &mut [Vec<char>], m: Move) -> Result<(), String> {
+    for stack in [m.from, m.to] {
+        if stack == 0 || stack > stacks.len() {
+            return Err(format!(
+                "stack {} doesn't exist (there are {})",
+                stack,
+                stacks.len()
+            ));
+        }
+    }
+    let available = stacks[m.from - 1].len();
+    if m.count > available {
+        return Err(format!(
+            "can't move {} crates from stack {}, which only has {}",
+            m.count, m.from, available
+        ));
+    }
+
+    // Each lift goes straight back where it came from.
+    if m.from == m.to {
+        return Ok(());
+    }
+
+    let lifted = stacks[m.from - 1].split_off(available - m.count);
+    let landed = crane.rearrange(lifted);
+    stacks[m.to - 1].extend(landed);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stacks() -> Vec<Vec<char>> {
+        vec![vec!['Z', 'N'], vec!['M', 'C', 'D'], vec!['P']]
+    }
+
+    #[test]
+    fn test_cranes() {
+        let m = Move {
+            count: 3,
+            from: 2,
+            to: 3,
+        };
+        let mut one = stacks();
+        apply(&CrateMover9000, &mut one, m).unwrap();
+        assert_eq!(one[2], vec!['P', 'D', 'C', 'M']);
+
+        let mut all = stacks();
+        apply(&CrateMover9001, &mut all, m).unwrap();
+        assert_eq!(all[2], vec!['P', 'M', 'C', 'D']);
+
+        let mut two = stacks();
+        apply(by_name("lift:2").unwrap().as_ref(), &mut two, m).unwrap();
+        assert_eq!(two[2], vec!['P', 'C', 'D', 'M']);
+        assert_eq!(
+            by_name("lift:1").unwrap().rearrange(vec!['A', 'B']),
+            vec!['B', 'A']
+        );
+        assert!(by_name("lift:0").is_none());
+    }
+
+    #[test]
+    fn test_invalid_moves() {
+        let mut s = stacks();
+        let too_many = Move {
+            count: 3,
+            from: 1,
+            to: 2,
+        };
+        assert!(apply(&CrateMover9000, &mut s, too_many).is_err());
+        let missing = Move {
+            count: 1,
+            from: 1,
+            to: 4,
+        };
+        assert!(apply(&CrateMover9001, &mut s, missing).is_err());
+        assert_eq!(s, stacks());
+
+        // Moving onto the same stack puts the crates back.
+        let same = Move {
+            count: 2,
+            from: 2,
+            to: 2,
+        };
+        apply(&CrateMover9000, &mut s, same).unwrap();
+        assert_eq!(s, stacks());
+    }
+}
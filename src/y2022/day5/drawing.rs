@@ -0,0 +1,195 @@
+// Reads and draws stacks of crates in the puzzle's format, e.g.
+//
+//     [D]
+// [N] [C]
+// [Z] [M] [P]
+//  1   2   3
+//
+// The last line labels each stack, and every crate belongs to the label it sits
+// above. Labels can be more than one character wide (e.g. "10"), in which case
+// the columns only need to line up with some part of the label.
+use crate::parsing::{self, ParseError, ParseResult};
+
+pub fn parse(section: &parsing::Paragraph) -> ParseResult<Vec<Vec<char>>> {
+    let (label_line, rows) = match section.lines.split_last() {
+        Some((label_line, rows)) => (label_line, rows),
+        None => return Err(ParseError::new(section.line, "missing stack drawing")),
+    };
+    let label_line_num = section.line + rows.len();
+
+    // Each label covers the columns [start, end).
+    let labels = tokens(label_line);
+    if labels.is_empty() {
+        return Err(ParseError::new(label_line_num, "missing stack labels"));
+    }
+    for (i, (_, _, label)) in labels.iter().enumerate() {
+        if label.parse::<usize>().ok() != Some(i + 1) {
+            return Err(ParseError::new(
+                label_line_num,
+                format!("expected stack {}, found '{}'", i + 1, label),
+            ));
+        }
+    }
+
+    // Read from the bottom up, so each stack is in the correct order.
+    let mut stacks: Vec<Vec<char>> = vec![Vec::new(); labels.len()];
+    for (level, (line_num, row)) in section
+        .numbered_lines()
+        .take(rows.len())
+        .collect::<Vec<_>>()
+        .into_iter()
+        .rev()
+        .enumerate()
+    {
+        for (start, end, token) in tokens(row) {
+            let label = match token.strip_prefix('[').and_then(|t| t.strip_suffix(']')) {
+                Some(label) if label.chars().count() == 1 => label.chars().next().unwrap(),
+                _ => {
+                    return Err(ParseError::new(
+                        line_num,
+                        format!("expected a crate like '[A]', found '{}'", token),
+                    ))
+                }
+            };
+
+            // The crate's middle column has to be under one of the labels.
+            let middle = start + (end - start) / 2;
+            let stack = labels
+                .iter()
+                .position(|(label_start, label_end, _)| {
+                    (*label_start..*label_end).contains(&middle)
+                })
+                .ok_or_else(|| {
+                    ParseError::new(
+                        line_num,
+                        format!("crate [{}] isn't above a stack label", label),
+                    )
+                })?;
+            if stacks[stack].len() != level {
+                return Err(ParseError::new(
+                    line_num,
+                    format!("crate [{}] is floating above stack {}", label, stack + 1),
+                ));
+            }
+            stacks[stack].push(label);
+        }
+    }
+    Ok(stacks)
+}
+
+// Splits a line into whitespace-separated tokens, with the character columns
+// each one covers.
+fn tokens(line: &str) -> Vec<(usize, usize, String)> {
+    let mut found = Vec::new();
+    let mut current: Option<(usize, String)> = None;
+    for (col, c) in line.chars().enumerate() {
+        if c.is_whitespace() {
+            if let Some((start, token)) = current.take() {
+                found.push((start, col, token));
+            }
+        } else {
+            current
+                .get_or_insert_with(|| (col, String::new()))
+                .1
+                .push(c);
+        }
+    }
+    if let Some((start, token)) = current {
+        let end = start + token.chars().count();
+        found.push((start, end, token));
+    }
+    found
+}
+
+// Draws the stacks in the same format that `parse` reads. Columns are three
+// characters wide, or wider if the labels need more room.
+pub fn render(stacks: &[Vec<char>]) -> String {
+    let label_width = stacks.len().to_string().len();
+    let width = 3.max(label_width + 1);
+    let height = stacks.iter().map(|stack| stack.len()).max().unwrap_or(0);
+
+    let mut lines: Vec<String> = (0..height)
+        .rev()
+        .map(|level| {
+            stacks
+                .iter()
+                .map(|stack| match stack.get(level) {
+                    Some(label) => format!("{:<width$}", format!("[{}]", label), width = width),
+                    None => " ".repeat(width),
+                })
+                .collect::<Vec<_>>()
+                .join(" ")
+                .trim_end()
+                .to_string()
+        })
+        .collect();
+    lines.push(
+        (1..=stacks.len())
+            .map(|number| format!(" {:<width$}", number, width = width - 1))
+            .collect::<Vec<_>>()
+            .join(" "),
+    );
+    lines.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "    [D]
+[N] [C]
+[Z] [M] [P]
+ 1   2   3 ";
+
+    fn parse_str(drawing: &str) -> ParseResult<Vec<Vec<char>>> {
+        parse(&parsing::paragraphs(drawing)[0])
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let stacks = parse_str(EXAMPLE).unwrap();
+        assert_eq!(stacks, vec![vec!['Z', 'N'], vec!['M', 'C', 'D'], vec!['P']]);
+        assert_eq!(render(&stacks), EXAMPLE);
+
+        // Ten or more stacks have two digit labels, and a hundred or more need
+        // wider columns.
+        for count in [10, 12, 100, 101] {
+            let stacks: Vec<Vec<char>> = (0..count)
+                .map(|i| {
+                    (0..i % 4)
+                        .map(|j| (b'A' + ((i + j) % 26) as u8) as char)
+                        .collect()
+                })
+                .collect();
+            assert_eq!(parse_str(&render(&stacks)).unwrap(), stacks);
+        }
+
+        // Empty stacks still get a label.
+        let stacks = vec![vec![], vec!['A'], vec![]];
+        assert_eq!(parse_str(&render(&stacks)).unwrap(), stacks);
+    }
+
+    #[test]
+    fn test_hand_drawn() {
+        // Trailing spaces are often lost, and labels only need to overlap.
+        let drawing = "[A]                                     [B]\n\
+                       [C]                                     [D]\n \
+                       1   2   3   4   5   6   7   8   9   10  11";
+        let stacks = parse_str(drawing).unwrap();
+        assert_eq!(stacks.len(), 11);
+        assert_eq!(stacks[0], vec!['C', 'A']);
+        assert_eq!(stacks[10], vec!['D', 'B']);
+    }
+
+    #[test]
+    fn test_bad_drawings() {
+        let floating = "[A]\n    [B]\n 1   2 ";
+        assert_eq!(parse_str(floating).unwrap_err().line, 1);
+        let unlabelled = "        [A]\n 1   2 ";
+        assert_eq!(parse_str(unlabelled).unwrap_err().line, 1);
+        let out_of_order = "[A]\n 1   3 ";
+        assert_eq!(parse_str(out_of_order).unwrap_err().line, 2);
+        let not_a_crate = "[AB]\n 1 ";
+        assert_eq!(parse_str(not_a_crate).unwrap_err().line, 1);
+    }
+}
@@ -0,0 +1,138 @@
+use crate::answer::Answer;
+use crate::helpers::Args;
+use crate::parsing::{self, ParseError, ParseResult, Pattern};
+use crane::{Crane, CrateMover9000, CrateMover9001, Move};
+use std::thread;
+use std::time::Duration;
+
+pub mod crane;
+pub mod drawing;
+
+pub fn crate_rearrangement(input: &str, part_two: bool, args: &Args) -> ParseResult<Answer> {
+    // An empty line separates the two sections of the input.
+    let [stack_section, command_section] = parsing::sections::<2>(input)?;
+
+    eprintln!("Input stack: \n{}", stack_section.text());
+
+    // The data model is a vector of "stacks." Each stack is a vector of chars.
+    // stack[0] is then the first stack of crates to rearrange. stack[0].pop()
+    // would remove the top crate (represented by a character)
+    let mut stacks = drawing::parse(&stack_section)?;
+    eprintln!("\nParsed stacks:");
+    print_stack(&stacks);
+
+    // Part one uses the CrateMover 9000 and part two the 9001, but any crane
+    // can be picked with "--crane 9000", "--crane 9001" or "--crane lift:N".
+    let crane: Box<dyn Crane> = match args.value("--crane") {
+        Some(name) => match crane::by_name(name) {
+            Some(crane) => crane,
+            None => {
+                return Ok(Answer::Unsolved(format!(
+                    "unknown crane '{}', use 9000, 9001 or lift:N",
+                    name
+                )))
+            }
+        },
+        None if part_two => Box::new(CrateMover9001),
+        None => Box::new(CrateMover9000),
+    };
+    eprintln!("\nUsing the {}.", crane.name());
+
+    // This will execute the instructions, modifying the stacks in place.
+    let playback = Playback::from_args(args);
+    let invalid_moves =
+        execute_instructions(&command_section, &mut stacks, crane.as_ref(), playback)?;
+    eprintln!("\nStacks after rearranging:");
+    print_stack(&stacks);
+
+    if let Some(first) = invalid_moves.first() {
+        return Ok(Answer::Unsolved(format!(
+            "{} invalid move{}, starting with {}",
+            invalid_moves.len(),
+            if invalid_moves.len() == 1 { "" } else { "s" },
+            first
+        )));
+    }
+
+    // For each stack, get the top box and put it in the string.
+    let top_boxes: String = stacks.iter().filter_map(|stack| stack.last()).collect();
+    Ok(top_boxes.into())
+}
+
+fn print_stack(stacks: &[Vec<char>]) {
+    eprintln!("{}", drawing::render(stacks));
+}
+
+// How to show the stacks while the crane works. "--playback" prints the
+// stacks after every move, and "--animate [ms]" redraws them in place.
+#[derive(Clone, Copy)]
+enum Playback {
+    Off,
+    Print,
+    Animate(Duration),
+}
+
+impl Playback {
+    fn from_args(args: &Args) -> Playback {
+        if args.has("--animate") {
+            // The delay is optional, so whatever follows might be another flag.
+            let ms = args
+                .value("--animate")
+                .and_then(|ms| ms.parse::<u64>().ok())
+                .unwrap_or(100);
+            Playback::Animate(Duration::from_millis(ms))
+        } else if args.has("--playback") {
+            Playback::Print
+        } else {
+            Playback::Off
+        }
+    }
+
+    fn show(&self, step: usize, command: &str, stacks: &[Vec<char>]) {
+        match self {
+            Playback::Off => (),
+            Playback::Print => {
+                eprintln!("\nStep {}: {}\n{}", step, command, drawing::render(stacks))
+            }
+            Playback::Animate(delay) => {
+                // Clear the screen and move the cursor to the top left.
+                eprint!("\x1b[2J\x1b[H");
+                eprintln!("Step {}: {}\n\n{}", step, command, drawing::render(stacks));
+                thread::sleep(*delay);
+            }
+        }
+    }
+}
+
+// Execute each command one-by-one. Moves which can't be carried out are
+// reported and skipped, and returned so the caller can decide what to do.
+fn execute_instructions(
+    instructions: &parsing::Paragraph,
+    stacks: &mut [Vec<char>],
+    crane: &dyn Crane,
+    playback: Playback,
+) -> ParseResult<Vec<ParseError>> {
+    let command_pattern = Pattern::new("move {count} from {from} to {to}");
+    let mut invalid_moves = Vec::new();
+    for (step, (line_num, cmd_str)) in instructions.numbered_lines().enumerate() {
+        let command = str_to_command(&command_pattern, cmd_str, line_num)?;
+        if let Err(e) = crane::apply(crane, stacks, command) {
+            let invalid = ParseError::new(line_num, e);
+            eprintln!("Invalid move on {}", invalid);
+            invalid_moves.push(invalid);
+        }
+        playback.show(step + 1, cmd_str.trim(), stacks);
+    }
+    Ok(invalid_moves)
+}
+
+// Input is in the form of "move A from B to C", where A, B, and C are ints.
+pub fn str_to_command(command_pattern: &Pattern, command: &str, line: usize) -> ParseResult<Move> {
+    let fields = command_pattern.parse(command, line)?;
+
+    Ok(Move {
+        count: fields.get("count")?,
+        from: fields.get("from")?,
+        to: fields.get("to")?,
+    })
+}
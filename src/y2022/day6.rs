@@ -1,40 +1,218 @@
-use std::collections::{HashSet, VecDeque};
+use crate::answer::Answer;
+use crate::helpers::Args;
+use crate::parsing::ParseResult;
+use std::collections::VecDeque;
+use std::fs::File;
+use std::io::{self, Read};
 
-pub fn fix_comms_device(input: &String, part_two: bool) -> String {
+pub fn fix_comms_device(input: &str, part_two: bool, args: &Args) -> ParseResult<Answer> {
     // In part one, the num of unique chars required is 4. In part two, it's 14.
-    let chars_to_check = if part_two { 14 } else { 4 };
+    // Any other size can be used with "--window N".
+    let window = match args.parsed::<usize>("--window") {
+        Ok(window) => window.unwrap_or(if part_two { 14 } else { 4 }),
+        Err(e) => return Ok(Answer::Unsolved(e)),
+    };
+    if window == 0 {
+        return Ok(Answer::Unsolved("the window can't be empty".to_string()));
+    }
 
-    // This primes the starter vec with the first few chars so that the main loop
-    // below can start by checking for duplicates. The minus one means that in part
-    // one, this vector is only 3 characters. With one pushed at the start of the
-    // loop, it becomes 4.
-    let (starter_chars, rest) = input.lines().next().unwrap().split_at(chars_to_check - 1);
+    // A random signal can have millions of markers, so only the first few are
+    // kept. "--all-markers" lists every one as it's found instead.
+    let all_markers = args.has("--all-markers");
+    let mut first_few = Vec::new();
+    let mut count = 0u64;
+    let mut found = |position| {
+        count += 1;
+        if all_markers {
+            eprintln!("Marker at {}", position);
+        } else if first_few.len() < 10 {
+            first_few.push(position);
+        }
+    };
 
-    // The answer is the first iteration this vec contains unique characters.
-    let mut last_four_chars = starter_chars.chars().collect::<VecDeque<_>>();
+    // "--signal-file path" streams the signal from a file rather than reading
+    // the whole puzzle input, for signals too big to keep around.
+    let first = match args.value("--signal-file") {
+        Some(path) => {
+            match File::open(path).and_then(|file| find_markers(file, window, &mut found)) {
+                Ok(first) => first,
+                Err(e) => return Ok(Answer::Unsolved(format!("could not read {}: {}", path, e))),
+            }
+        }
+        None => find_markers(input.as_bytes(), window, &mut found)
+            .expect("reading from memory can't fail"),
+    };
+
+    eprintln!("Found {} markers of {} distinct characters", count, window);
+    if !all_markers && count > 0 {
+        eprintln!(
+            "First at: {:?}{}",
+            first_few,
+            if count > first_few.len() as u64 {
+                " ..."
+            } else {
+                ""
+            }
+        );
+    }
 
-    for (position, c) in rest.chars().enumerate() {
-        last_four_chars.push_back(c);
+    match first {
+        Some(first) => Ok(first.into()),
+        None => Ok(Answer::Unsolved(
+            "did not find non-duplicated groups".to_string(),
+        )),
+    }
+}
+
+// Tracks the last `window` bytes of a signal, and how many times each byte
+// value appears among them. Pushing a byte only updates the counts for the byte
+// coming in and the one going out, so checking for a marker is O(1) no matter
+// how big the window is.
+struct MarkerDetector {
+    window: usize,
+    recent: VecDeque<u8>,
+    counts: [usize; 256],
+    // How many byte values appear more than once in the window.
+    duplicated: usize,
+}
 
-        if !are_duplicated_letters(&last_four_chars) {
-            // Our iteration is offset by the number of chars we collected into
-            // the starter vec, so add it. (This also ultimately accounts for the
-            // answer being 1-indexed rather than 0-indexed.)
-            return (position + chars_to_check).to_string();
+impl MarkerDetector {
+    fn new(window: usize) -> MarkerDetector {
+        MarkerDetector {
+            window,
+            recent: VecDeque::with_capacity(window + 1),
+            counts: [0; 256],
+            duplicated: 0,
+        }
+    }
+
+    // Adds a byte, returning true if the last `window` bytes are all different.
+    fn push(&mut self, byte: u8) -> bool {
+        self.recent.push_back(byte);
+        self.counts[byte as usize] += 1;
+        if self.counts[byte as usize] == 2 {
+            self.duplicated += 1;
         }
 
-        // Remove first char, since it has been completely checked.
-        last_four_chars.pop_front();
+        if self.recent.len() > self.window {
+            let old = self.recent.pop_front().unwrap();
+            self.counts[old as usize] -= 1;
+            if self.counts[old as usize] == 1 {
+                self.duplicated -= 1;
+            }
+        }
+        self.recent.len() == self.window && self.duplicated == 0
     }
+}
+
+// Calls `found` with the position of every marker in the signal, as soon as
+// it's read: the number of bytes read when the last `window` bytes were all
+// different. Any byte counts, newlines included, except for a line ending at
+// the very end. Returns the first marker, if there is one.
+pub fn find_markers(
+    mut signal: impl Read,
+    window: usize,
+    mut found: impl FnMut(usize),
+) -> io::Result<Option<usize>> {
+    let mut detector = MarkerDetector::new(window);
+    let mut first = None;
+    let mut position = 0;
+    let mut push = |byte: u8| {
+        position += 1;
+        if detector.push(byte) {
+            first.get_or_insert(position);
+            found(position);
+        }
+    };
 
-    return "Did not find non-duplicated groups...".to_string();
+    // The last two bytes are held back until we know whether they're the
+    // line ending at the end of the signal.
+    let mut held: VecDeque<u8> = VecDeque::with_capacity(3);
+    let mut buffer = [0u8; 64 * 1024];
+    loop {
+        let read = match signal.read(&mut buffer) {
+            Ok(0) => break,
+            Ok(read) => read,
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+            Err(e) => return Err(e),
+        };
+        for byte in &buffer[..read] {
+            held.push_back(*byte);
+            if held.len() > 2 {
+                push(held.pop_front().unwrap());
+            }
+        }
+    }
+    if held.back() == Some(&b'\n') {
+        held.pop_back();
+        if held.back() == Some(&b'\r') {
+            held.pop_back();
+        }
+    }
+    held.into_iter().for_each(push);
+    Ok(first)
 }
 
-// This function looks like O(chars.len()), but since that is always 4, it's constant time.
-fn are_duplicated_letters(chars: &VecDeque<char>) -> bool {
-    // Collect the chars into a HashSet, which will remove duplicates.
-    let set = chars.iter().collect::<HashSet<_>>();
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Hands out one byte per read, to make sure nothing depends on how the
+    // signal is split up.
+    struct Trickle<'a>(&'a [u8]);
+
+    impl Read for Trickle<'_> {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            match self.0.split_first() {
+                Some((first, rest)) if !buf.is_empty() => {
+                    buf[0] = *first;
+                    self.0 = rest;
+                    Ok(1)
+                }
+                _ => Ok(0),
+            }
+        }
+    }
+
+    fn markers(signal: impl Read, window: usize) -> Vec<usize> {
+        let mut markers = Vec::new();
+        let first = find_markers(signal, window, |position| markers.push(position)).unwrap();
+        assert_eq!(first, markers.first().copied());
+        markers
+    }
+
+    fn first_marker(signal: &str, window: usize) -> Option<usize> {
+        find_markers(signal.as_bytes(), window, |_| ()).unwrap()
+    }
+
+    #[test]
+    fn test_examples() {
+        let examples = [
+            ("mjqjpqmgbljsphdztnvjfqwrcgsmlb", 7, 19),
+            ("bvwbjplbgvbhsrlpgdmjqwftvncz", 5, 23),
+            ("nppdvjthqldpwncqszvftbrmjlhg", 6, 23),
+            ("nznrnfrfntjfmvfwmzdfjlvtqnbhcprsg", 10, 29),
+            ("zcfzfwzzqfrljwzlrfnpqdbhtmscgvjw", 11, 26),
+        ];
+        for (signal, packet, message) in examples {
+            assert_eq!(first_marker(signal, 4), Some(packet));
+            assert_eq!(first_marker(signal, 14), Some(message));
+        }
+    }
 
-    // If the set is less than the chars, there were duplicates.
-    return set.len() < chars.len();
+    #[test]
+    fn test_every_marker() {
+        assert_eq!(markers(&b"aabcabb"[..], 3), vec![4, 5, 6]);
+        assert_eq!(markers(&b"aaaa"[..], 2), Vec::<usize>::new());
+        assert_eq!(markers(&b"ab"[..], 3), Vec::<usize>::new());
+        assert_eq!(markers(&b"\xff\x00\xff"[..], 2), vec![2, 3]);
+
+        // Newlines are bytes like any other, apart from a line ending at the
+        // end of the signal.
+        assert_eq!(markers(Trickle(b"a\nb"), 3), vec![3]);
+        assert_eq!(markers(Trickle(b"ab\r\n"), 3), Vec::<usize>::new());
+        assert_eq!(markers(Trickle(b"ab\n"), 2), vec![2]);
+        assert_eq!(markers(Trickle(b"a\n\n"), 2), vec![2]);
+        assert_eq!(markers(Trickle(b"\r\n\r"), 2), vec![2, 3]);
+    }
 }
@@ -0,0 +1,138 @@
+// Exports the rebuilt filesystem, either as nested JSON or as real files on
+// disk. The files are sparse (created with set_len), so even the puzzle's 48MB
+// of "files" take up almost no space. `du -ab` on the exported directory lists
+// the same file sizes, though real directories add a few KB of their own.
+use super::fs::{FileSystem, FS, ROOT};
+use serde_json::{json, Value};
+use std::fs::{self, File};
+use std::io;
+use std::path::Path;
+
+pub fn to_json(fs: &FileSystem) -> Value {
+    entry_to_json(fs, ROOT)
+}
+
+fn entry_to_json(fs: &FileSystem, index: usize) -> Value {
+    let entry = fs.get(index);
+    match entry.kind {
+        FS::File => json!({
+            "name": entry.name,
+            "type": "file",
+            "size": entry.size,
+        }),
+        FS::Dir => json!({
+            "name": entry.name,
+            "type": "dir",
+            "size": entry.size,
+            "children": entry
+                .children
+                .iter()
+                .map(|child| entry_to_json(fs, *child))
+                .collect::<Vec<_>>(),
+        }),
+    }
+}
+
+// Creates the filesystem under `target`, which must not exist yet or be empty.
+// That way a typo can't scatter files through an existing directory.
+pub fn materialize(fs: &FileSystem, target: &Path) -> io::Result<()> {
+    if target.exists() && fs::read_dir(target)?.next().is_some() {
+        return Err(io::Error::new(
+            io::ErrorKind::AlreadyExists,
+            format!("{} is not empty", target.display()),
+        ));
+    }
+    // Names come straight from the transcript, so make sure none of them can
+    // point outside the target.
+    if let Some((_, entry)) = fs
+        .entries()
+        .skip(1)
+        .find(|(_, e)| e.name == "." || e.name == ".." || e.name.contains(['/', '\\']))
+    {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("can't export an entry named '{}'", entry.name),
+        ));
+    }
+
+    fs::create_dir_all(target)?;
+    for index in fs.walk(ROOT).into_iter().skip(1) {
+        let entry = fs.get(index);
+        // The path starts with "/", which would replace the target when joined.
+        let path = target.join(&fs.path(index)[1..]);
+        match entry.kind {
+            FS::Dir => fs::create_dir(&path)?,
+            FS::File => File::create(&path)?.set_len(entry.size)?,
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    fn example() -> FileSystem {
+        let mut fs = FileSystem::new();
+        let a = fs.add_dir(ROOT, "a");
+        fs.add_file(a, "f", 29116).unwrap();
+        fs.add_file(ROOT, "b.txt", 14848514).unwrap();
+        fs
+    }
+
+    fn temp_dir(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("aoc-day7-{}-{}", name, std::process::id()))
+    }
+
+    // Adds up the sizes of every file under a directory.
+    fn disk_usage(path: &Path) -> u64 {
+        fs::read_dir(path)
+            .unwrap()
+            .map(|entry| {
+                let entry = entry.unwrap();
+                let metadata = entry.metadata().unwrap();
+                if metadata.is_dir() {
+                    disk_usage(&entry.path())
+                } else {
+                    metadata.len()
+                }
+            })
+            .sum()
+    }
+
+    #[test]
+    fn test_to_json() {
+        let json = to_json(&example());
+        assert_eq!(json["size"], 14877630);
+        assert_eq!(json["children"][0]["name"], "a");
+        assert_eq!(json["children"][0]["children"][0]["size"], 29116);
+        assert_eq!(json["children"][1]["type"], "file");
+        assert!(json["children"][1].get("children").is_none());
+    }
+
+    #[test]
+    fn test_materialize() {
+        let fs = example();
+        let target = temp_dir("export");
+        materialize(&fs, &target).unwrap();
+        let a_size = fs::metadata(target.join("a/f")).map(|m| m.len());
+        let total = disk_usage(&target);
+        // Exporting again on top of the files isn't allowed.
+        let again = materialize(&fs, &target);
+        fs::remove_dir_all(&target).unwrap();
+
+        assert_eq!(a_size.unwrap(), 29116);
+        assert_eq!(total, fs.size(ROOT));
+        assert!(again.is_err());
+    }
+
+    #[test]
+    fn test_materialize_rejects_escaping_names() {
+        let mut fs = example();
+        fs.add_dir(ROOT, "..");
+        let target = temp_dir("escape");
+        assert!(materialize(&fs, &target).is_err());
+        assert!(!target.exists());
+    }
+}
@@ -0,0 +1,198 @@
+// An in-memory filesystem rebuilt from a terminal transcript. Entries live in
+// an arena and refer to each other by index, with the root directory always at
+// index 0. Directory sizes are kept up to date as files are added, so asking
+// for the size of any entry is cheap.
+use std::collections::HashMap;
+
+pub const ROOT: usize = 0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FS {
+    Dir,
+    File,
+}
+
+#[derive(Debug)]
+pub struct FileSystemEntry {
+    pub name: String,
+    pub kind: FS,
+    // For files this is the file's size, and for directories it's the total
+    // size of everything under them.
+    pub size: u64,
+    pub parent: Option<usize>,
+    // Children in the order they were first listed, plus a lookup by name.
+    pub children: Vec<usize>,
+    child_index: HashMap<String, usize>,
+}
+
+#[derive(Debug)]
+pub struct FileSystem {
+    entries: Vec<FileSystemEntry>,
+}
+
+impl Default for FileSystem {
+    fn default() -> FileSystem {
+        FileSystem::new()
+    }
+}
+
+impl FileSystem {
+    pub fn new() -> FileSystem {
+        FileSystem {
+            entries: vec![FileSystemEntry {
+                name: "/".to_string(),
+                kind: FS::Dir,
+                size: 0,
+                parent: None,
+                children: Vec::new(),
+                child_index: HashMap::new(),
+            }],
+        }
+    }
+
+    pub fn get(&self, index: usize) -> &FileSystemEntry {
+        &self.entries[index]
+    }
+
+    pub fn entries(&self) -> impl Iterator<Item = (usize, &FileSystemEntry)> {
+        self.entries.iter().enumerate()
+    }
+
+    pub fn dirs(&self) -> impl Iterator<Item = (usize, &FileSystemEntry)> {
+        self.entries().filter(|(_, entry)| entry.kind == FS::Dir)
+    }
+
+    pub fn size(&self, index: usize) -> u64 {
+        self.entries[index].size
+    }
+
+    // Finds a child of a directory by name.
+    pub fn child(&self, dir: usize, name: &str) -> Option<usize> {
+        self.entries[dir].child_index.get(name).copied()
+    }
+
+    // Adds a directory, or returns the existing entry with that name.
+    pub fn add_dir(&mut self, parent: usize, name: &str) -> usize {
+        match self.child(parent, name) {
+            Some(existing) => existing,
+            None => self.push(parent, name, FS::Dir, 0),
+        }
+    }
+
+    // Adds a file, or returns the existing entry with that name. Sizes of an
+    // existing file are left alone. Fails without changing anything if a
+    // directory's total size would overflow.
+    pub fn add_file(&mut self, parent: usize, name: &str, size: u64) -> Result<usize, String> {
+        if let Some(existing) = self.child(parent, name) {
+            return Ok(existing);
+        }
+        let mut ancestor = Some(parent);
+        while let Some(dir) = ancestor {
+            if self.entries[dir].size.checked_add(size).is_none() {
+                return Err(format!("{} is too big to add up", self.path(dir)));
+            }
+            ancestor = self.entries[dir].parent;
+        }
+
+        let index = self.push(parent, name, FS::File, size);
+        let mut ancestor = Some(parent);
+        while let Some(dir) = ancestor {
+            self.entries[dir].size += size;
+            ancestor = self.entries[dir].parent;
+        }
+        Ok(index)
+    }
+
+    fn push(&mut self, parent: usize, name: &str, kind: FS, size: u64) -> usize {
+        let index = self.entries.len();
+        self.entries.push(FileSystemEntry {
+            name: name.to_string(),
+            kind,
+            size,
+            parent: Some(parent),
+            children: Vec::new(),
+            child_index: HashMap::new(),
+        });
+        self.entries[parent].children.push(index);
+        self.entries[parent]
+            .child_index
+            .insert(name.to_string(), index);
+        index
+    }
+
+    // Resolves a path like "/a/b", "../e" or "." from the given directory.
+    // Going above the root stays at the root, like a real shell.
+    pub fn resolve(&self, from: usize, path: &str) -> Option<usize> {
+        let mut current = if path.starts_with('/') { ROOT } else { from };
+        for part in path.split('/') {
+            current = match part {
+                "" | "." => current,
+                ".." => self.entries[current].parent.unwrap_or(ROOT),
+                name => self.child(current, name)?,
+            };
+        }
+        Some(current)
+    }
+
+    // The absolute path of an entry, e.g. "/a/e/i".
+    pub fn path(&self, index: usize) -> String {
+        let mut names = Vec::new();
+        let mut current = index;
+        while let Some(parent) = self.entries[current].parent {
+            names.push(self.entries[current].name.as_str());
+            current = parent;
+        }
+        names.reverse();
+        format!("/{}", names.join("/"))
+    }
+
+    // Every entry under (and including) the given one, depth first.
+    pub fn walk(&self, index: usize) -> Vec<usize> {
+        let mut order = Vec::new();
+        let mut stack = vec![index];
+        while let Some(current) = stack.pop() {
+            order.push(current);
+            stack.extend(self.entries[current].children.iter().rev());
+        }
+        order
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sizes_and_paths() {
+        let mut fs = FileSystem::new();
+        let a = fs.add_dir(ROOT, "a");
+        let e = fs.add_dir(a, "e");
+        fs.add_file(e, "i", 584).unwrap();
+        fs.add_file(a, "f", 29116).unwrap();
+        fs.add_file(ROOT, "b.txt", 14848514).unwrap();
+        // Listing the same file again doesn't count it twice.
+        fs.add_file(a, "f", 29116).unwrap();
+
+        assert_eq!(fs.size(e), 584);
+        assert_eq!(fs.size(a), 29700);
+        assert_eq!(fs.size(ROOT), 14878214);
+        assert_eq!(fs.path(fs.child(e, "i").unwrap()), "/a/e/i");
+        assert_eq!(fs.path(ROOT), "/");
+        assert_eq!(fs.walk(a).len(), 4);
+    }
+
+    #[test]
+    fn test_resolve() {
+        let mut fs = FileSystem::new();
+        let a = fs.add_dir(ROOT, "a");
+        let e = fs.add_dir(a, "e");
+        let d = fs.add_dir(ROOT, "d");
+
+        assert_eq!(fs.resolve(ROOT, "/a/e"), Some(e));
+        assert_eq!(fs.resolve(e, "../../d"), Some(d));
+        assert_eq!(fs.resolve(e, "/"), Some(ROOT));
+        assert_eq!(fs.resolve(ROOT, "../.."), Some(ROOT));
+        assert_eq!(fs.resolve(a, "./e/"), Some(e));
+        assert_eq!(fs.resolve(a, "x"), None);
+    }
+}
@@ -0,0 +1,221 @@
+use crate::answer::Answer;
+use crate::helpers::Args;
+use crate::history;
+use crate::parsing::ParseResult;
+use fs::{FileSystem, FS, ROOT};
+use query::Shell;
+use std::env;
+use std::io::{self, BufRead, Write};
+use std::path::PathBuf;
+
+mod export;
+mod fs;
+mod query;
+pub mod transcript;
+
+pub fn directory_sizes(input: &str, part_two: bool, args: &Args) -> ParseResult<Answer> {
+    // Problems with the transcript are warnings, unless "--strict" is passed.
+    let transcript::Replay { fs, issues } = transcript::replay(input)?;
+    let strict = args.has("--strict");
+    for issue in &issues {
+        eprintln!("{}: {}", if strict { "Error" } else { "Warning" }, issue);
+    }
+    if let (true, Some(first)) = (strict, issues.into_iter().next()) {
+        return Err(first);
+    }
+    print_tree(&fs, ROOT, 0);
+
+    // Explore the filesystem with "--query 'du -h; largest 5'", a file of
+    // queries with "--query-file path", or a prompt with "--interactive".
+    if let Some(script) = args.value("--query") {
+        run_queries(&fs, script.split(';'));
+    }
+    if let Some(path) = args.value("--query-file") {
+        match std::fs::read_to_string(path) {
+            Ok(script) => run_queries(&fs, script.lines()),
+            Err(e) => eprintln!("Could not read {}: {}", path, e),
+        }
+    }
+    // "--export-json path" writes the tree as nested JSON, and "--export-dir
+    // [path]" recreates it as sparse files (by default in a temp directory).
+    if let Some(path) = args.value("--export-json") {
+        let json = serde_json::to_string_pretty(&export::to_json(&fs)).unwrap();
+        match std::fs::write(path, json) {
+            Ok(_) => eprintln!("Wrote the filesystem to {}", path),
+            Err(e) => eprintln!("Could not write {}: {}", path, e),
+        }
+    }
+    if args.has("--export-dir") {
+        let target = match args.value("--export-dir") {
+            Some(path) if !path.starts_with("--") => PathBuf::from(path),
+            _ => env::temp_dir().join(format!("aoc-day7-{}", history::now())),
+        };
+        match export::materialize(&fs, &target) {
+            Ok(_) => eprintln!(
+                "Created the filesystem in {}. Check it with: du -ab {}",
+                target.display(),
+                target.display()
+            ),
+            Err(e) => eprintln!("Could not export to {}: {}", target.display(), e),
+        }
+    }
+    if args.has("--interactive") {
+        interactive(&fs);
+    }
+
+    if !part_two {
+        return Ok(sum_dirs_smaller_than(&fs, 100000).into());
+    }
+
+    // The puzzle's disk is 70000000 big, and the update needs 30000000 free.
+    // Use "--disk-size" and "--space-needed" to try other numbers.
+    let fs_size = match args.parsed::<u64>("--disk-size") {
+        Ok(fs_size) => fs_size.unwrap_or(70000000),
+        Err(e) => return Ok(Answer::Unsolved(e)),
+    };
+    let space_needed = match args.parsed::<u64>("--space-needed") {
+        Ok(space_needed) => space_needed.unwrap_or(30000000),
+        Err(e) => return Ok(Answer::Unsolved(e)),
+    };
+    let total_size = fs.size(ROOT);
+    if total_size > fs_size {
+        return Ok(Answer::Unsolved(format!(
+            "the files ({}) don't fit on the disk ({})",
+            total_size, fs_size
+        )));
+    }
+    let space_remaining = fs_size - total_size;
+    if space_remaining >= space_needed {
+        return Ok(Answer::Unsolved(format!(
+            "there is already {} free, nothing needs deleting",
+            space_remaining
+        )));
+    }
+    let find_space = space_needed - space_remaining;
+
+    eprintln!(
+        "\nTotal size: {}. FS size: {}. Space remaining: {}. Space to find: {}",
+        total_size, fs_size, space_remaining, find_space
+    );
+
+    match find_smallest_dir_at_least(&fs, find_space) {
+        Some(size) => Ok(size.into()),
+        None => Ok(Answer::Unsolved(format!(
+            "no directory frees up {}",
+            find_space
+        ))),
+    }
+}
+
+fn run_queries<'a>(fs: &FileSystem, queries: impl Iterator<Item = &'a str>) {
+    let mut shell = Shell::new(fs);
+    for query in queries.map(str::trim).filter(|q| !q.is_empty()) {
+        eprintln!("$ {}", query);
+        match shell.run(query) {
+            Ok(output) => eprint!("{}", output),
+            Err(e) => eprintln!("{}", e),
+        }
+    }
+}
+
+fn interactive(fs: &FileSystem) {
+    let mut shell = Shell::new(fs);
+    eprintln!("\nType a command (cd, pwd, tree, du, find, largest), or 'exit' to finish.");
+    loop {
+        eprint!("> ");
+        io::stderr().flush().unwrap();
+        let mut line = String::new();
+        match io::stdin().lock().read_line(&mut line) {
+            Ok(0) | Err(_) => break,
+            Ok(_) => (),
+        }
+        if matches!(line.trim(), "exit" | "quit") {
+            break;
+        }
+        match shell.run(&line) {
+            Ok(output) => eprint!("{}", output),
+            Err(e) => eprintln!("{}", e),
+        }
+    }
+}
+
+// Returns the size of the smallest directory which is at least the provided size.
+fn find_smallest_dir_at_least(fs: &FileSystem, size: u64) -> Option<u64> {
+    fs.dirs()
+        .map(|(_, entry)| entry.size)
+        .filter(|dir_size| *dir_size >= size)
+        .min()
+}
+
+// Returns the sum of all directories smaller than the provided size.
+fn sum_dirs_smaller_than(fs: &FileSystem, size: u64) -> u64 {
+    fs.dirs()
+        .map(|(_, entry)| entry.size)
+        .filter(|dir_size| *dir_size <= size)
+        .sum()
+}
+
+// Prints the entire tree to the terminal.
+fn print_tree(fs: &FileSystem, index: usize, depth: usize) {
+    let entry = fs.get(index);
+
+    eprintln!(
+        ">{:<width$} {}{} ({})",
+        "",
+        if entry.kind == FS::Dir { "--" } else { "" },
+        entry.name,
+        entry.size,
+        width = depth * 2,
+    );
+
+    for child in &entry.children {
+        print_tree(fs, *child, depth + 1);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "$ cd /
+$ ls
+dir a
+14848514 b.txt
+$ cd a
+$ ls
+dir e
+29116 f
+$ cd e
+$ ls
+584 i";
+
+    #[test]
+    fn test_options() {
+        let none = Args::default();
+        assert_eq!(
+            directory_sizes(EXAMPLE, false, &none).unwrap(),
+            Answer::from(30284u64)
+        );
+
+        let disk = Args::new(&["--disk-size", "14900000", "--space-needed", "30000"]);
+        assert_eq!(
+            directory_sizes(EXAMPLE, true, &disk).unwrap(),
+            Answer::from(29700u64)
+        );
+        let typo = Args::new(&["--disk-size", "70M"]);
+        assert!(matches!(
+            directory_sizes(EXAMPLE, true, &typo).unwrap(),
+            Answer::Unsolved(_)
+        ));
+
+        let wandering = format!("{}\n$ cd /\n$ cd ..", EXAMPLE);
+        assert!(directory_sizes(&wandering, false, &none).is_ok());
+        let strict = Args::new(&["--strict"]);
+        assert_eq!(
+            directory_sizes(&wandering, false, &strict)
+                .err()
+                .map(|e| e.line),
+            Some(13)
+        );
+    }
+}
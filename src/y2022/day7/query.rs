@@ -0,0 +1,271 @@
+// A tiny shell for poking around the reconstructed filesystem. Supports:
+//
+//   cd <path>                  change the current directory
+//   pwd                        print the current directory
+//   tree [path]                print the tree with sizes
+//   du [-h] [path]             size of every directory, children first
+//   find [path] [-type f|d] [-size [+|-]N[k|M|G]]
+//   largest [N]                the N largest directories (default 10)
+//
+// Unlike the real find, plain sizes are in bytes rather than 512 byte blocks.
+use super::fs::{FileSystem, FS, ROOT};
+
+pub struct Shell<'a> {
+    fs: &'a FileSystem,
+    cwd: usize,
+}
+
+impl<'a> Shell<'a> {
+    pub fn new(fs: &'a FileSystem) -> Shell<'a> {
+        Shell { fs, cwd: ROOT }
+    }
+
+    // Runs one command, returning its output or an error message.
+    pub fn run(&mut self, command: &str) -> Result<String, String> {
+        let args: Vec<&str> = command.split_whitespace().collect();
+        match args.as_slice() {
+            [] => Ok(String::new()),
+            ["cd"] => {
+                self.cwd = ROOT;
+                Ok(String::new())
+            }
+            ["cd", path] => {
+                let dir = self.resolve(path)?;
+                if self.fs.get(dir).kind != FS::Dir {
+                    return Err(format!("cd: {}: not a directory", path));
+                }
+                self.cwd = dir;
+                Ok(String::new())
+            }
+            ["pwd"] => Ok(format!("{}\n", self.fs.path(self.cwd))),
+            ["tree", rest @ ..] => {
+                let start = self.optional_path(rest)?;
+                Ok(self.tree(start))
+            }
+            ["du", rest @ ..] => {
+                let human = rest.contains(&"-h");
+                let paths: Vec<&str> = rest.iter().copied().filter(|a| *a != "-h").collect();
+                let start = self.optional_path(&paths)?;
+                Ok(self.du(start, human))
+            }
+            ["find", rest @ ..] => self.find(rest),
+            ["largest"] => Ok(self.largest(10)),
+            ["largest", count] => count
+                .parse::<usize>()
+                .map(|count| self.largest(count))
+                .map_err(|_| format!("largest: invalid count '{}'", count)),
+            [unknown, ..] => Err(format!("{}: command not found", unknown)),
+        }
+    }
+
+    fn resolve(&self, path: &str) -> Result<usize, String> {
+        self.fs
+            .resolve(self.cwd, path)
+            .ok_or_else(|| format!("{}: no such file or directory", path))
+    }
+
+    fn optional_path(&self, args: &[&str]) -> Result<usize, String> {
+        match args {
+            [] => Ok(self.cwd),
+            [path] => self.resolve(path),
+            _ => Err("expected at most one path".to_string()),
+        }
+    }
+
+    fn tree(&self, start: usize) -> String {
+        let depth_of = |index: usize| {
+            let mut depth = 0;
+            let mut current = index;
+            while current != start {
+                current = self.fs.get(current).parent.unwrap_or(start);
+                depth += 1;
+            }
+            depth
+        };
+        self.fs
+            .walk(start)
+            .into_iter()
+            .map(|index| {
+                let entry = self.fs.get(index);
+                let name = if index == start {
+                    self.fs.path(index)
+                } else {
+                    entry.name.clone()
+                };
+                format!(
+                    "{:width$}- {} ({}, size={})\n",
+                    "",
+                    name,
+                    if entry.kind == FS::Dir { "dir" } else { "file" },
+                    entry.size,
+                    width = depth_of(index) * 2
+                )
+            })
+            .collect()
+    }
+
+    // Like du, every directory is listed after its children.
+    fn du(&self, start: usize, human: bool) -> String {
+        self.fs
+            .walk(start)
+            .into_iter()
+            .rev()
+            .filter(|index| self.fs.get(*index).kind == FS::Dir)
+            .map(|index| {
+                let size = self.fs.size(index);
+                let size = if human {
+                    human_size(size)
+                } else {
+                    size.to_string()
+                };
+                format!("{}\t{}\n", size, self.fs.path(index))
+            })
+            .collect()
+    }
+
+    fn find(&self, args: &[&str]) -> Result<String, String> {
+        let mut start = self.cwd;
+        let mut kind = None;
+        let mut size_filter: Option<(std::cmp::Ordering, u64)> = None;
+
+        let mut args = args.iter();
+        while let Some(arg) = args.next() {
+            match *arg {
+                "-type" => {
+                    kind = match args.next() {
+                        Some(&"f") => Some(FS::File),
+                        Some(&"d") => Some(FS::Dir),
+                        _ => return Err("find: -type needs 'f' or 'd'".to_string()),
+                    }
+                }
+                "-size" => {
+                    let value = args.next().ok_or("find: -size needs a value")?;
+                    size_filter = Some(parse_size_filter(value)?);
+                }
+                path if !path.starts_with('-') => start = self.resolve(path)?,
+                other => return Err(format!("find: unknown option '{}'", other)),
+            }
+        }
+
+        Ok(self
+            .fs
+            .walk(start)
+            .into_iter()
+            .filter(|index| {
+                let entry = self.fs.get(*index);
+                kind.is_none_or(|kind| entry.kind == kind)
+                    && size_filter.is_none_or(|(ordering, size)| entry.size.cmp(&size) == ordering)
+            })
+            .map(|index| format!("{}\n", self.fs.path(index)))
+            .collect())
+    }
+
+    fn largest(&self, count: usize) -> String {
+        let mut dirs: Vec<usize> = self.fs.dirs().map(|(index, _)| index).collect();
+        dirs.sort_by_key(|index| std::cmp::Reverse(self.fs.size(*index)));
+        dirs.iter()
+            .take(count)
+            .map(|index| format!("{}\t{}\n", self.fs.size(*index), self.fs.path(*index)))
+            .collect()
+    }
+}
+
+// Parses a find size like "+100k": bigger than (+), smaller than (-), or
+// exactly (no sign) the given number of bytes.
+fn parse_size_filter(value: &str) -> Result<(std::cmp::Ordering, u64), String> {
+    let (ordering, rest) = match value.as_bytes().first() {
+        Some(b'+') => (std::cmp::Ordering::Greater, &value[1..]),
+        Some(b'-') => (std::cmp::Ordering::Less, &value[1..]),
+        _ => (std::cmp::Ordering::Equal, value),
+    };
+    let (number, multiplier) = match rest.chars().last() {
+        Some('c') => (&rest[..rest.len() - 1], 1),
+        Some('k') => (&rest[..rest.len() - 1], 1 << 10),
+        Some('M') => (&rest[..rest.len() - 1], 1 << 20),
+        Some('G') => (&rest[..rest.len() - 1], 1 << 30),
+        _ => (rest, 1),
+    };
+    number
+        .parse::<u64>()
+        .ok()
+        .and_then(|n| n.checked_mul(multiplier))
+        .map(|size| (ordering, size))
+        .ok_or_else(|| format!("find: invalid size '{}'", value))
+}
+
+// Formats a size like "du -h": powers of 1024, rounded up, with one decimal
+// place for small numbers. E.g. 1536 is "1.5K" and 14848514 is "15M".
+fn human_size(size: u64) -> String {
+    let units = ["K", "M", "G", "T", "P"];
+    if size < 1024 {
+        return size.to_string();
+    }
+    let mut value = size as f64;
+    let mut unit = 0;
+    value /= 1024.0;
+    while value >= 1024.0 && unit < units.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    if value < 10.0 {
+        format!("{:.1}{}", (value * 10.0).ceil() / 10.0, units[unit])
+    } else {
+        format!("{}{}", value.ceil(), units[unit])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn example() -> FileSystem {
+        let mut fs = FileSystem::new();
+        let a = fs.add_dir(ROOT, "a");
+        let e = fs.add_dir(a, "e");
+        fs.add_file(e, "i", 584).unwrap();
+        fs.add_file(a, "f", 29116).unwrap();
+        fs.add_file(a, "h.lst", 62596).unwrap();
+        fs.add_file(ROOT, "b.txt", 14848514).unwrap();
+        let d = fs.add_dir(ROOT, "d");
+        fs.add_file(d, "k", 7214296).unwrap();
+        fs
+    }
+
+    #[test]
+    fn test_du() {
+        let fs = example();
+        let mut shell = Shell::new(&fs);
+        assert_eq!(
+            shell.run("du").unwrap(),
+            "7214296\t/d\n584\t/a/e\n92296\t/a\n22155106\t/\n"
+        );
+        assert_eq!(shell.run("du -h /a").unwrap(), "584\t/a/e\n91K\t/a\n");
+        assert!(shell.run("du /nope").is_err());
+    }
+
+    #[test]
+    fn test_find_and_largest() {
+        let fs = example();
+        let mut shell = Shell::new(&fs);
+        assert_eq!(
+            shell.run("find -type f -size +1M").unwrap(),
+            "/b.txt\n/d/k\n"
+        );
+        assert_eq!(shell.run("find /a -type f -size -1k").unwrap(), "/a/e/i\n");
+        shell.run("cd a/e").unwrap();
+        assert_eq!(shell.run("pwd").unwrap(), "/a/e\n");
+        assert_eq!(shell.run("find .. -type d").unwrap(), "/a\n/a/e\n");
+        assert_eq!(
+            shell.run("largest 2").unwrap(),
+            "22155106\t/\n7214296\t/d\n"
+        );
+    }
+
+    #[test]
+    fn test_human_size() {
+        assert_eq!(human_size(584), "584");
+        assert_eq!(human_size(1536), "1.5K");
+        assert_eq!(human_size(14848514), "15M");
+        assert_eq!(human_size(3 << 30), "3.0G");
+    }
+}
@@ -0,0 +1,308 @@
+// Replays a terminal transcript of "$ cd" and "$ ls" commands to rebuild the
+// filesystem. Real transcripts are well behaved, but hand-written or generated
+// ones might not be. Anything inconsistent is recorded as an issue with its
+// line number, and the replay carries on with a best guess (the first listing
+// of an entry wins). Use --strict to treat issues as errors.
+use super::fs::{FileSystem, FS, ROOT};
+use crate::parsing::{self, ParseError, ParseResult};
+use std::collections::HashSet;
+
+pub struct Replay {
+    pub fs: FileSystem,
+    pub issues: Vec<ParseError>,
+}
+
+// The ls currently being read: which directory, where the command was, and
+// which entries it has listed so far.
+struct Listing {
+    dir: usize,
+    line: usize,
+    seen: HashSet<usize>,
+}
+
+pub fn replay(input: &str) -> ParseResult<Replay> {
+    let mut fs = FileSystem::new();
+    let mut issues = Vec::new();
+    let mut active_dir = ROOT;
+    let mut listing: Option<Listing> = None;
+    let mut listed_dirs: HashSet<usize> = HashSet::new();
+
+    // Each command starts with "$ " and is followed by some number of lines of
+    // output. The only command with output is ls, so any line which isn't a
+    // command is an entry listed by the most recent ls.
+    for (i, line) in input.lines().enumerate() {
+        let line_num = i + 1;
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let mut cmd_data = match line.strip_prefix("$ ") {
+            Some(cmd) => cmd.split_whitespace(),
+            None => {
+                // ls output. Entries either start with "dir" or the size if it's a file.
+                let mut parts = line.split_whitespace();
+                let (kind_or_size, name) = match (parts.next(), parts.next()) {
+                    (Some(kind_or_size), Some(name)) => (kind_or_size, name),
+                    _ => {
+                        return Err(ParseError::new(
+                            line_num,
+                            "expected 'dir <name>' or '<size> <name>'",
+                        ))
+                    }
+                };
+                let size = match kind_or_size {
+                    "dir" => None,
+                    size => Some(parsing::parse_value::<u64>(size, line_num, "file size")?),
+                };
+                if listing.is_none() {
+                    issues.push(ParseError::new(
+                        line_num,
+                        format!("'{}' is listed without an ls", name),
+                    ));
+                }
+                let index =
+                    add_listed_entry(&mut fs, active_dir, name, size, line_num, &mut issues)?;
+                if let Some(listing) = &mut listing {
+                    listing.seen.insert(index);
+                }
+                continue;
+            }
+        };
+
+        // Any command ends the output of the previous ls.
+        if let Some(finished) = listing.take() {
+            finish_listing(&fs, finished, &mut listed_dirs, &mut issues);
+        }
+
+        match cmd_data.next() {
+            Some("cd") => match cmd_data.next() {
+                Some(path) => {
+                    active_dir = change_dir(&mut fs, active_dir, path, line_num, &mut issues)
+                }
+                None => {
+                    return Err(ParseError::new(line_num, "cd needs a directory name!"));
+                }
+            },
+            Some("ls") => {
+                listing = Some(Listing {
+                    dir: active_dir,
+                    line: line_num,
+                    seen: HashSet::new(),
+                })
+            }
+            Some(unknown_cmd) => issues.push(ParseError::new(
+                line_num,
+                format!("unknown command '{}'", unknown_cmd),
+            )),
+            None => issues.push(ParseError::new(line_num, "empty command")),
+        }
+    }
+    if let Some(finished) = listing.take() {
+        finish_listing(&fs, finished, &mut listed_dirs, &mut issues);
+    }
+
+    Ok(Replay { fs, issues })
+}
+
+// Adds an entry from ls output, checking it against anything already listed
+// with the same name.
+fn add_listed_entry(
+    fs: &mut FileSystem,
+    dir: usize,
+    name: &str,
+    size: Option<u64>,
+    line: usize,
+    issues: &mut Vec<ParseError>,
+) -> ParseResult<usize> {
+    if let Some(existing) = fs.child(dir, name) {
+        let entry = fs.get(existing);
+        let path = fs.path(existing);
+        match (entry.kind, size) {
+            (FS::File, Some(size)) if size != entry.size => issues.push(ParseError::new(
+                line,
+                format!(
+                    "file {} is listed with size {}, but was {} before",
+                    path, size, entry.size
+                ),
+            )),
+            (FS::File, None) => issues.push(ParseError::new(
+                line,
+                format!("{} is listed as a directory, but was a file before", path),
+            )),
+            (FS::Dir, Some(size)) => issues.push(ParseError::new(
+                line,
+                format!(
+                    "{} is listed as a file of size {}, but was a directory before",
+                    path, size
+                ),
+            )),
+            _ => (),
+        }
+        return Ok(existing);
+    }
+    match size {
+        Some(size) => fs
+            .add_file(dir, name, size)
+            .map_err(|e| ParseError::new(line, e)),
+        None => Ok(fs.add_dir(dir, name)),
+    }
+}
+
+// A directory listed twice should have the same entries both times. Otherwise
+// its size depends on which listing is believed.
+fn finish_listing(
+    fs: &FileSystem,
+    listing: Listing,
+    listed_dirs: &mut HashSet<usize>,
+    issues: &mut Vec<ParseError>,
+) {
+    if !listed_dirs.insert(listing.dir) {
+        for child in &fs.get(listing.dir).children {
+            if !listing.seen.contains(child) {
+                issues.push(ParseError::new(
+                    listing.line,
+                    format!(
+                        "ls of {} doesn't list {}, so its size conflicts with an earlier ls",
+                        fs.path(listing.dir),
+                        fs.path(*child)
+                    ),
+                ));
+            }
+        }
+    }
+}
+
+// Follows a cd path like a shell would. Directories that haven't been listed
+// yet are created, since they must exist for cd to work.
+fn change_dir(
+    fs: &mut FileSystem,
+    from: usize,
+    path: &str,
+    line: usize,
+    issues: &mut Vec<ParseError>,
+) -> usize {
+    let mut current = if path.starts_with('/') { ROOT } else { from };
+    for part in path.split('/') {
+        current = match part {
+            "" | "." => current,
+            ".." => match fs.get(current).parent {
+                Some(parent) => parent,
+                None => {
+                    issues.push(ParseError::new(line, "cd above the root directory"));
+                    ROOT
+                }
+            },
+            name => match fs.child(current, name) {
+                Some(child) if fs.get(child).kind == FS::Dir => child,
+                Some(child) => {
+                    issues.push(ParseError::new(
+                        line,
+                        format!("cd into {}, which is a file", fs.path(child)),
+                    ));
+                    return current;
+                }
+                None => {
+                    let dir = fs.add_dir(current, name);
+                    issues.push(ParseError::new(
+                        line,
+                        format!("cd into {}, which hasn't been listed", fs.path(dir)),
+                    ));
+                    dir
+                }
+            },
+        };
+    }
+    current
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rng::Rng;
+    use crate::y2022::generate::{self, Size};
+
+    const EXAMPLE: &str = "$ cd /
+$ ls
+dir a
+14848514 b.txt
+$ cd a
+$ ls
+dir e
+29116 f
+$ cd e
+$ ls
+584 i";
+
+    #[test]
+    fn test_replay() {
+        let replay = replay(EXAMPLE).unwrap();
+        assert!(replay.issues.is_empty());
+        assert_eq!(replay.fs.size(ROOT), 14878214);
+
+        assert!(super::replay("$ cd /\n$ ls\nfoo bar\n").is_err());
+        // Files which fit on their own, but not in the same directory.
+        let too_big = super::replay("$ cd /\n$ ls\n18446744073709551615 a\n1 b");
+        assert_eq!(too_big.err().map(|e| e.line), Some(4));
+    }
+
+    #[test]
+    fn test_issues() {
+        let transcript = format!(
+            "{}\n$ cd /\n$ cd ..\n$ cd d\n$ cd /a\n$ ls\n30000 f\n$ rm -rf /\n12 nowhere",
+            EXAMPLE
+        );
+        let issues: Vec<(usize, String)> = replay(&transcript)
+            .unwrap()
+            .issues
+            .into_iter()
+            .map(|issue| (issue.line, issue.message))
+            .collect();
+        assert_eq!(
+            issues,
+            vec![
+                (13, "cd above the root directory".to_string()),
+                (14, "cd into /d, which hasn't been listed".to_string()),
+                (
+                    17,
+                    "file /a/f is listed with size 30000, but was 29116 before".to_string()
+                ),
+                (
+                    16,
+                    "ls of /a doesn't list /a/e, so its size conflicts with an earlier ls"
+                        .to_string()
+                ),
+                (18, "unknown command 'rm'".to_string()),
+                (19, "'nowhere' is listed without an ls".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_dir_sizes_add_up() {
+        for seed in 0..20 {
+            let input =
+                generate::generate(7, Size::new(seed * 10), &mut Rng::new(seed as u64)).unwrap();
+            let replay = replay(&input).unwrap();
+            assert!(
+                replay.issues.is_empty(),
+                "seed {}: {:?}",
+                seed,
+                replay.issues
+            );
+            for (index, dir) in replay.fs.dirs() {
+                let children: u64 = dir
+                    .children
+                    .iter()
+                    .map(|child| replay.fs.size(*child))
+                    .sum();
+                assert_eq!(
+                    replay.fs.size(index),
+                    children,
+                    "seed {}: {}",
+                    seed,
+                    replay.fs.path(index)
+                );
+            }
+        }
+    }
+}
@@ -1,6 +1,9 @@
-use std::collections::HashSet;
+use crate::answer::Answer;
+use crate::helpers::Args;
+use crate::parsing::{self, ParseError, ParseResult};
+use crate::ppm::{self, Image};
 
-pub fn check_tree_visibility(input: &String, part_two: bool) -> String {
+pub fn check_tree_visibility(input: &str, part_two: bool, args: &Args) -> ParseResult<Answer> {
     // Example input, with expected result of 21.
     // let input = "30373
     //              25512
@@ -8,131 +11,300 @@ pub fn check_tree_visibility(input: &String, part_two: bool) -> String {
     //              33549
     //              35390";
 
-    // Collect input to a 2d array of numbers.
-    let trees: Vec<Vec<i16>> = input
-        .lines()
-        .map(|line| {
-            line.chars()
-                .filter_map(|c| match c.to_digit(10) {
-                    Some(d) => Some(d as i16), // Have to use match to get the i16 type.
-                    None => None, // Handle none so we can have spaces in the input, like above.
-                })
-                .collect()
-        })
-        .collect();
+    // "--integers" reads each row as whitespace-separated integers instead of
+    // one character per tree.
+    let trees = parse_forest(input, args.has("--integers"))?;
+
+    eprintln!("Tree size: {}x{}", trees.len(), trees[0].len());
+    let forest = survey(&trees);
+
+    // Part one maps which trees are visible, and part two the scenic scores.
+    let heat: Vec<Vec<f64>> = if part_two {
+        let best = forest.best_score().max(1) as f64;
+        forest
+            .scores
+            .iter()
+            .map(|row| row.iter().map(|score| *score as f64 / best).collect())
+            .collect()
+    } else {
+        forest
+            .visible
+            .iter()
+            .map(|row| row.iter().map(|v| if *v { 1.0 } else { 0.0 }).collect())
+            .collect()
+    };
+    // "--heatmap path.ppm" saves the map as an image, and "--heatmap-terminal"
+    // prints each tree's height coloured by its value.
+    if let Some(path) = args.value("--heatmap") {
+        match heat_map(&heat).save(path) {
+            Ok(_) => eprintln!("Saved the heat map to {}", path),
+            Err(e) => eprintln!("Could not write {}: {}", path, e),
+        }
+    }
+    if args.has("--heatmap-terminal") {
+        eprintln!("{}", terminal_heat_map(&trees, &heat));
+    }
 
-    println!("Tree size: {}x{}", trees.len(), trees[0].len());
     if part_two {
-        get_best_scenic_score(&trees).to_string()
+        Ok(forest.best_score().into())
     } else {
-        let seen_trees = get_visible_trees(&trees);
-        seen_trees.len().to_string()
+        Ok(forest.visible_count().into())
     }
 }
 
-fn get_best_scenic_score(trees: &Vec<Vec<i16>>) -> i32 {
-    let mut scores: Vec<i32> = Vec::new();
-
-    // Ideally, fold would work here... But then we don't have the tree indices,
-    // which means we can't use them to start further iterations.
-    for h in 0..trees.len() {
-        for w in 0..trees[0].len() {
-            let tree = trees[h][w];
-
-            let mut num_up = 0;
-            for h2 in (0..h).rev() {
-                num_up += 1;
-                if trees[h2][w] >= tree {
-                    break;
-                }
-            }
-
-            let mut num_down = 0;
-            for h2 in h + 1..trees.len() {
-                num_down += 1;
-                if trees[h2][w] >= tree {
-                    break;
-                }
+// Heights are usually single digits, e.g. "30373". Forests can also use any
+// other characters, which are ordered by their code point (so after '9' come
+// ':', ';', ... 'A', ... 'a' and so on). With `integers`, cells are separated
+// by whitespace and each one is an integer instead, e.g. "3 0 -2 17".
+pub fn parse_forest(input: &str, integers: bool) -> ParseResult<Vec<Vec<i64>>> {
+    let lines: Vec<(usize, &str)> = input
+        .lines()
+        .enumerate()
+        .map(|(i, line)| (i + 1, line.trim()))
+        .filter(|(_, line)| !line.is_empty())
+        .collect();
+    let mut trees: Vec<Vec<i64>> = Vec::new();
+    for (line_num, line) in lines {
+        let row: Vec<i64> = if integers {
+            line.split_whitespace()
+                .map(|cell| parsing::parse_value(cell, line_num, "tree height"))
+                .collect::<ParseResult<_>>()?
+        } else if line.contains(char::is_whitespace) {
+            return Err(ParseError::new(
+                line_num,
+                "trees can't be whitespace, use --integers for whitespace-separated heights",
+            ));
+        } else {
+            line.chars().map(|c| c as i64 - '0' as i64).collect()
+        };
+        if let Some(first) = trees.first() {
+            if first.len() != row.len() {
+                return Err(ParseError::new(
+                    line_num,
+                    format!(
+                        "row has {} trees, but the first row has {}",
+                        row.len(),
+                        first.len()
+                    ),
+                ));
             }
+        }
+        trees.push(row);
+    }
 
-            let mut num_right = 0;
-            for w2 in w + 1..trees[0].len() {
-                num_right += 1;
-                if trees[h][w2] >= tree {
-                    break;
-                }
-            }
+    if trees.is_empty() {
+        return Err(ParseError::new(1, "the forest is empty"));
+    }
+    Ok(trees)
+}
 
-            let mut num_left = 0;
-            for w2 in (0..w).rev() {
-                num_left += 1;
-                if trees[h][w2] >= tree {
-                    break;
-                }
-            }
+// Which trees can be seen from outside the forest, and each tree's scenic
+// score (the product of how far it can see in each direction).
+struct Forest {
+    visible: Vec<Vec<bool>>,
+    scores: Vec<Vec<u64>>,
+}
 
-            // Save the total score for each iteration
-            scores.push(num_up * num_down * num_right * num_left);
-        }
+impl Forest {
+    fn visible_count(&self) -> usize {
+        self.visible.iter().flatten().filter(|v| **v).count()
     }
 
-    *scores.iter().max().unwrap_or(&0)
+    fn best_score(&self) -> u64 {
+        self.scores.iter().flatten().max().copied().unwrap_or(0)
+    }
 }
 
-fn get_visible_trees(trees: &Vec<Vec<i16>>) -> HashSet<String> {
-    let mut seen_trees: HashSet<String> = HashSet::new();
-
+// Looks along every row and column from both ends, which visits each tree four
+// times in total.
+fn survey(trees: &[Vec<i64>]) -> Forest {
     let height = trees.len();
-    let width = trees[0].len();
+    let width = trees.first().map_or(0, |row| row.len());
+    let mut forest = Forest {
+        visible: vec![vec![false; width]; height],
+        scores: vec![vec![1; width]; height],
+    };
 
-    // TODO: I'd really love to make this more generic, but I'm not sure how.
+    for h in 0..height {
+        let row: Vec<(usize, usize)> = (0..width).map(|w| (h, w)).collect();
+        look_along(trees, &row, &mut forest);
+        let reversed: Vec<(usize, usize)> = row.into_iter().rev().collect();
+        look_along(trees, &reversed, &mut forest);
+    }
     for w in 0..width {
-        let mut tallest_tree = -1;
-        // Because Rust is VERY strict with memory access, we can't use it multiple
-        // times in this width for loop. So we have to generate the ranges again.
-        for h in 0..height {
-            if add_tallest_trees(&mut seen_trees, &trees, h, w, &mut tallest_tree) {
-                break;
-            }
+        let column: Vec<(usize, usize)> = (0..height).map(|h| (h, w)).collect();
+        look_along(trees, &column, &mut forest);
+        let reversed: Vec<(usize, usize)> = column.into_iter().rev().collect();
+        look_along(trees, &reversed, &mut forest);
+    }
+    forest
+}
+
+// Walks a line of trees starting from the edge. A tree is visible from that
+// edge if it's taller than everything before it, and it can see back towards
+// the edge as far as the closest tree which is at least as tall.
+//
+// The closest taller tree is found with a stack of trees which are still
+// "blocking" (each shorter than the one below it). Anything shorter than the
+// current tree can't block a later tree that this one doesn't, so it's popped.
+fn look_along(trees: &[Vec<i64>], line: &[(usize, usize)], forest: &mut Forest) {
+    let mut tallest: Option<i64> = None;
+    let mut blocking: Vec<usize> = Vec::new();
+
+    for (i, (h, w)) in line.iter().enumerate() {
+        let tree = trees[*h][*w];
+        if tallest.is_none_or(|tallest| tree > tallest) {
+            tallest = Some(tree);
+            forest.visible[*h][*w] = true;
         }
-        tallest_tree = -1;
-        for h in (0..height).rev() {
-            if add_tallest_trees(&mut seen_trees, &trees, h, w, &mut tallest_tree) {
-                break;
-            }
+
+        while blocking
+            .last()
+            .is_some_and(|j| trees[line[*j].0][line[*j].1] < tree)
+        {
+            blocking.pop();
         }
+        let distance = match blocking.last() {
+            Some(j) => i - j,
+            None => i,
+        };
+        forest.scores[*h][*w] *= distance as u64;
+        blocking.push(i);
     }
-    for h in 0..height {
-        let mut tallest_tree = -1;
-        for w in 0..width {
-            if add_tallest_trees(&mut seen_trees, &trees, h, w, &mut tallest_tree) {
-                break;
-            }
+}
+
+fn heat_map(heat: &[Vec<f64>]) -> Image {
+    let width = heat.first().map_or(0, |row| row.len());
+    let mut image = Image::new(width, heat.len(), [0, 0, 0]);
+    for (h, row) in heat.iter().enumerate() {
+        for (w, value) in row.iter().enumerate() {
+            image.set(w, h, ppm::heat(*value));
+        }
+    }
+    image.scaled(4)
+}
+
+// Prints every tree's height on a background coloured by its value, using the
+// 24-bit colour escape codes most terminals support.
+fn terminal_heat_map(trees: &[Vec<i64>], heat: &[Vec<f64>]) -> String {
+    // Pad heights to the same width, so the columns line up.
+    let cell_width = trees
+        .iter()
+        .flatten()
+        .map(|tree| tree.to_string().len())
+        .max()
+        .unwrap_or(1);
+    let mut out = String::new();
+    for (row, heat_row) in trees.iter().zip(heat) {
+        for (tree, value) in row.iter().zip(heat_row) {
+            let [r, g, b] = ppm::heat(*value);
+            // Dark text on bright cells, and light text on dark ones.
+            let text = if (r as u32 + g as u32 + b as u32) > 380 {
+                0
+            } else {
+                255
+            };
+            out.push_str(&format!(
+                "\x1b[48;2;{};{};{}m\x1b[38;2;{t};{t};{t}m{:>width$}",
+                r,
+                g,
+                b,
+                tree,
+                t = text,
+                width = cell_width
+            ));
         }
-        tallest_tree = -1;
-        for w in (0..width).rev() {
-            if add_tallest_trees(&mut seen_trees, &trees, h, w, &mut tallest_tree) {
-                break;
+        out.push_str("\x1b[0m\n");
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Scans outwards from every tree, the slow but obvious way.
+    fn survey_slowly(trees: &[Vec<i64>]) -> Forest {
+        let (height, width) = (trees.len(), trees[0].len());
+        let mut forest = Forest {
+            visible: vec![vec![false; width]; height],
+            scores: vec![vec![0; width]; height],
+        };
+        for h in 0..height {
+            for w in 0..width {
+                let tree = trees[h][w];
+                let directions: [Vec<i64>; 4] = [
+                    (0..h).rev().map(|o| trees[o][w]).collect(),
+                    (h + 1..height).map(|o| trees[o][w]).collect(),
+                    (0..w).rev().map(|o| trees[h][o]).collect(),
+                    (w + 1..width).map(|o| trees[h][o]).collect(),
+                ];
+                forest.visible[h][w] = directions
+                    .iter()
+                    .any(|others| others.iter().all(|other| *other < tree));
+                forest.scores[h][w] = directions
+                    .iter()
+                    .map(
+                        |others| match others.iter().position(|other| *other >= tree) {
+                            Some(blocked) => blocked as u64 + 1,
+                            None => others.len() as u64,
+                        },
+                    )
+                    .product();
             }
         }
+        forest
     }
 
-    return seen_trees;
-}
+    #[test]
+    fn test_parse_forest() {
+        assert_eq!(
+            parse_forest("303\n255\n", false).unwrap(),
+            vec![vec![3, 0, 3], vec![2, 5, 5]]
+        );
+        // Letters are taller than digits.
+        assert_eq!(parse_forest("9aA", false).unwrap(), vec![vec![9, 49, 17]]);
+        assert_eq!(
+            parse_forest("  10 -2 300\n1   2   3\n", true).unwrap(),
+            vec![vec![10, -2, 300], vec![1, 2, 3]]
+        );
+        assert_eq!(parse_forest("1 2\n3 x", true).unwrap_err().line, 2);
+        assert_eq!(parse_forest("30 3\n255", false).unwrap_err().line, 1);
 
-fn add_tallest_trees(
-    set: &mut HashSet<String>,
-    trees: &Vec<Vec<i16>>,
-    h: usize,
-    w: usize,
-    tallest_tree: &mut i16,
-) -> bool {
-    let tree = trees[h][w];
-    if tree > *tallest_tree {
-        *tallest_tree = tree;
-        set.insert(format!("{},{}", h, w));
+        // A column of single integers has no whitespace to give the mode away,
+        // so it's only read as integers when asked to.
+        assert_eq!(
+            parse_forest("10\n3", true).unwrap(),
+            vec![vec![10], vec![3]]
+        );
+        assert_eq!(parse_forest("10\n3", false).unwrap_err().line, 2);
+
+        let ragged = parse_forest("303\n25\n", false).unwrap_err();
+        assert_eq!(ragged.line, 2);
+        assert_eq!(ragged.message, "row has 2 trees, but the first row has 3");
+        assert!(parse_forest("\n\n", false).is_err());
     }
 
-    // 9 is the max height, so we should stop looking if we see it.
-    tree >= 9
+    #[test]
+    fn test_survey() {
+        let example = vec![
+            vec![3, 0, 3, 7, 3],
+            vec![2, 5, 5, 1, 2],
+            vec![6, 5, 3, 3, 2],
+            vec![3, 3, 5, 4, 9],
+            vec![3, 5, 3, 9, 0],
+        ];
+        let forest = survey(&example);
+        assert_eq!(forest.visible_count(), 21);
+        assert_eq!(forest.scores[3][2], 8);
+
+        // Heights aren't limited to digits, and the forest doesn't have to be
+        // square.
+        let trees: Vec<Vec<i64>> = (0..13)
+            .map(|h| (0..29).map(|w| (h * 31 + w * 17) % 23 - 5).collect())
+            .collect();
+        let (fast, slow) = (survey(&trees), survey_slowly(&trees));
+        assert_eq!(fast.visible, slow.visible);
+        assert_eq!(fast.scores, slow.scores);
+    }
 }
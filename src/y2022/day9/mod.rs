@@ -0,0 +1,150 @@
+use crate::answer::Answer;
+use crate::helpers::Args;
+use crate::parsing::ParseResult;
+use crate::ppm::{self, Image};
+use rope::{Coord, Rope, Visits};
+use segments::Path;
+use std::collections::HashMap;
+use std::thread;
+use std::time::Duration;
+
+pub mod rope;
+mod segments;
+
+pub fn rope_shenanigans(input: &str, part_two: bool, args: &Args) -> ParseResult<Answer> {
+    // let input = "R 4
+    //              U 4
+    //              L 3
+    //              D 1
+    //              R 4
+    //              D 1
+    //              L 5
+    //              R 2"; // Expected answer is 13.
+    let moves = rope::parse_moves(input)?;
+
+    // The puzzle's ropes have 2 and 10 knots, but "--knots N" can make any
+    // length of rope.
+    let num_knots = match args.parsed::<usize>("--knots") {
+        Ok(num_knots) => num_knots.unwrap_or(if part_two { 10 } else { 2 }),
+        Err(e) => return Ok(Answer::Unsolved(e)),
+    };
+    if num_knots == 0 {
+        return Ok(Answer::Unsolved(
+            "a rope needs at least one knot".to_string(),
+        ));
+    }
+
+    // "--fast" works out each knot's path a straight run at a time, and counts
+    // the tail's cells without visiting them. Good for very long moves, but it
+    // can't show anything along the way.
+    if args.has("--fast") {
+        let mut path = Path::from_moves(&moves);
+        for _ in 1..num_knots {
+            path = path.follower();
+        }
+        return Ok(path.distinct_cells().into());
+    }
+
+    // "--animate [ms]" draws the rope after every step.
+    let animate = args.has("--animate").then(|| {
+        let ms = args
+            .value("--animate")
+            .and_then(|ms| ms.parse::<u64>().ok())
+            .unwrap_or(50);
+        Duration::from_millis(ms)
+    });
+
+    let mut rope = Rope::new(num_knots);
+    let mut visits = Visits::new(&rope);
+    for (step, (direction, num_steps)) in moves
+        .iter()
+        .flat_map(|(direction, num_steps)| (0..*num_steps).map(move |_| (*direction, *num_steps)))
+        .enumerate()
+    {
+        rope.step(direction);
+        visits.record(&rope);
+        if let Some(delay) = animate {
+            // Clear the screen and move the cursor to the top left.
+            eprint!("\x1b[2J\x1b[H");
+            eprintln!(
+                "Step {}: {:?} {}\n\n{}",
+                step + 1,
+                direction,
+                num_steps,
+                render_frame(&rope, &visits.knots[num_knots - 1])
+            );
+            thread::sleep(delay);
+        }
+    }
+
+    eprintln!("Tail ended at {:?}", rope.tail());
+
+    // "--heatmap path.ppm" saves how often each cell was visited, by the tail
+    // or by the knot given with "--heatmap-knot N" (0 is the head).
+    if let Some(path) = args.value("--heatmap") {
+        let knot = match args.parsed::<usize>("--heatmap-knot") {
+            Ok(knot) => knot.unwrap_or(num_knots - 1),
+            Err(e) => return Ok(Answer::Unsolved(e)),
+        };
+        match visits.knots.get(knot) {
+            Some(cells) => match heat_map(cells).save(path) {
+                Ok(_) => eprintln!("Saved the heat map for knot {} to {}", knot, path),
+                Err(e) => eprintln!("Could not write {}: {}", path, e),
+            },
+            None => eprintln!("The rope only has knots 0 to {}", num_knots - 1),
+        }
+    }
+
+    Ok(visits.knots[num_knots - 1].len().into())
+}
+
+// Draws the area around the head like the puzzle does: "H" for the head,
+// numbers for the knots behind it, and "#" for cells the tail has been to.
+fn render_frame(rope: &Rope, tail_visits: &HashMap<Coord, u64>) -> String {
+    // Only show what fits comfortably in a terminal.
+    let (half_width, half_height) = (40, 15);
+    let head = rope.knots[0];
+    let mut frame = String::new();
+    for y in (head.y - half_height..=head.y + half_height).rev() {
+        for x in head.x - half_width..=head.x + half_width {
+            let here = Coord { x, y };
+            let cell = match rope.knots.iter().position(|knot| *knot == here) {
+                Some(0) => 'H',
+                Some(i) if i < 10 => char::from_digit(i as u32, 10).unwrap(),
+                Some(_) => '*',
+                None if here == Coord::default() => 's',
+                None if tail_visits.contains_key(&here) => '#',
+                None => '.',
+            };
+            frame.push(cell);
+        }
+        frame.push('\n');
+    }
+    frame
+}
+
+// Colours each visited cell by how many steps were spent there, on a log
+// scale since the start tends to be visited far more than anywhere else.
+fn heat_map(cells: &HashMap<Coord, u64>) -> Image {
+    let min_x = cells.keys().map(|c| c.x).min().unwrap_or(0);
+    let max_x = cells.keys().map(|c| c.x).max().unwrap_or(0);
+    let min_y = cells.keys().map(|c| c.y).min().unwrap_or(0);
+    let max_y = cells.keys().map(|c| c.y).max().unwrap_or(0);
+    let most = cells.values().copied().max().unwrap_or(1) as f64;
+
+    let mut image = Image::new(
+        (max_x - min_x + 1) as usize,
+        (max_y - min_y + 1) as usize,
+        [0, 0, 0],
+    );
+    for (cell, count) in cells {
+        let value = (*count as f64).ln_1p() / most.ln_1p();
+        // Images go top to bottom, but y goes up.
+        image.set(
+            (cell.x - min_x) as usize,
+            (max_y - cell.y) as usize,
+            ppm::heat(value),
+        );
+    }
+    image.scaled(4)
+}
@@ -0,0 +1,279 @@
+// A rope made of knots. The head is moved one step at a time, and every other
+// knot follows the knot in front of it using the "rope rules": a knot that's
+// no longer touching the one ahead moves one step (diagonally if needed)
+// towards it.
+use crate::parsing::{ParseError, ParseResult, Pattern};
+use std::collections::HashMap;
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Dir {
+    R,
+    L,
+    U,
+    D,
+}
+
+impl Dir {
+    // The change in position from one step in this direction.
+    pub fn delta(self) -> Coord {
+        match self {
+            Dir::R => Coord { x: 1, y: 0 },
+            Dir::L => Coord { x: -1, y: 0 },
+            Dir::U => Coord { x: 0, y: 1 },
+            Dir::D => Coord { x: 0, y: -1 },
+        }
+    }
+}
+
+#[derive(Eq, Hash, PartialEq, Copy, Clone, Debug, Default, PartialOrd, Ord)]
+pub struct Coord {
+    pub x: i64,
+    pub y: i64,
+}
+
+impl Coord {
+    // Moves a coordinate one step in the given direction.
+    pub fn move_towards(&mut self, direction: Dir) {
+        *self = *self + direction.delta();
+    }
+
+    // Moves a coordinate closer to the given coord, following "rope rules."
+    pub fn follow(&mut self, to_coord: Coord) {
+        // Scenario one: touching coordinates do not move.
+        if self.touching(to_coord) {
+            return;
+        }
+
+        // Otherwise, move one step towards the other coord on each axis where
+        // they differ.
+        self.x += (to_coord.x - self.x).signum();
+        self.y += (to_coord.y - self.y).signum();
+    }
+
+    // Returns true if the two coordinates are within one x,y of each other in any direction.
+    // Overlapping counts as touching.
+    pub fn touching(&self, other: Coord) -> bool {
+        (self.x - other.x).abs() <= 1 && (self.y - other.y).abs() <= 1
+    }
+}
+
+impl std::ops::Add for Coord {
+    type Output = Coord;
+
+    fn add(self, other: Coord) -> Coord {
+        Coord {
+            x: self.x + other.x,
+            y: self.y + other.y,
+        }
+    }
+}
+
+impl std::ops::Sub for Coord {
+    type Output = Coord;
+
+    fn sub(self, other: Coord) -> Coord {
+        Coord {
+            x: self.x - other.x,
+            y: self.y - other.y,
+        }
+    }
+}
+
+impl std::ops::Mul<i64> for Coord {
+    type Output = Coord;
+
+    fn mul(self, by: i64) -> Coord {
+        Coord {
+            x: self.x * by,
+            y: self.y * by,
+        }
+    }
+}
+
+pub struct Rope {
+    // knots[0] is the head, and the last knot is the tail.
+    pub knots: Vec<Coord>,
+}
+
+impl Rope {
+    // A rope needs at least one knot. With only one, the head is the tail.
+    pub fn new(num_knots: usize) -> Rope {
+        Rope {
+            knots: vec![Coord::default(); num_knots.max(1)],
+        }
+    }
+
+    pub fn tail(&self) -> Coord {
+        *self.knots.last().unwrap()
+    }
+
+    // Moves the head one step, and each knot after it follows the one before.
+    pub fn step(&mut self, direction: Dir) {
+        self.knots[0].move_towards(direction);
+        for i in 1..self.knots.len() {
+            // The "to_knot" is knot changing, so we can clone it.
+            let to_knot = self.knots[i - 1];
+            self.knots[i].follow(to_knot);
+        }
+    }
+}
+
+// Counts how many steps each knot spent on each cell, including the start.
+pub struct Visits {
+    pub knots: Vec<HashMap<Coord, u64>>,
+}
+
+impl Visits {
+    pub fn new(rope: &Rope) -> Visits {
+        let mut visits = Visits {
+            knots: vec![HashMap::new(); rope.knots.len()],
+        };
+        visits.record(rope);
+        visits
+    }
+
+    pub fn record(&mut self, rope: &Rope) {
+        for (knot, position) in rope.knots.iter().enumerate() {
+            *self.knots[knot].entry(*position).or_insert(0) += 1;
+        }
+    }
+}
+
+pub fn parse_moves(input: &str) -> ParseResult<Vec<(Dir, i64)>> {
+    let move_pattern = Pattern::new("{direction} {steps}");
+    input
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(i, line)| {
+            let fields = move_pattern.parse(line, i + 1)?;
+            let direction = match fields.str("direction")? {
+                "R" => Dir::R,
+                "L" => Dir::L,
+                "U" => Dir::U,
+                "D" => Dir::D,
+                other => {
+                    return Err(ParseError::new(
+                        i + 1,
+                        format!("unknown direction '{}'", other),
+                    ))
+                }
+            };
+            let steps = fields.get::<i64>("steps")?;
+            if steps < 0 {
+                return Err(ParseError::new(i + 1, "steps can't be negative"));
+            }
+            Ok((direction, steps))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rng::Rng;
+    use crate::y2022::generate::{self, Size};
+
+    #[test]
+    fn test_follow_equal() {
+        let mut coord_1 = Coord { x: 1, y: 2 };
+
+        assert_eq!(coord_1, Coord { x: 1, y: 2 });
+
+        coord_1.follow(Coord { x: 1, y: 2 });
+        assert_eq!(coord_1, Coord { x: 1, y: 2 });
+    }
+
+    #[test]
+    fn test_follow_xy() {
+        let mut coord_1 = Coord { x: 1, y: 2 };
+
+        coord_1.follow(Coord { x: 1, y: 4 });
+        assert_eq!(coord_1, Coord { x: 1, y: 3 }); // y plus one.
+
+        coord_1.follow(Coord { x: -1, y: 3 });
+        assert_eq!(coord_1, Coord { x: 0, y: 3 }); // x minus one.
+
+        coord_1.follow(Coord { x: 0, y: 1 });
+        assert_eq!(coord_1, Coord { x: 0, y: 2 }); // y minus one.
+
+        coord_1.follow(Coord { x: 3, y: 2 });
+        assert_eq!(coord_1, Coord { x: 1, y: 2 }); // x plus one.
+    }
+
+    #[test]
+    fn test_follow_diag() {
+        let mut coord_1 = Coord { x: 1, y: 1 };
+        coord_1.follow(Coord { x: 2, y: 3 });
+        assert_eq!(coord_1, Coord { x: 2, y: 2 });
+
+        let mut coord_2 = Coord { x: 1, y: 1 };
+        coord_2.follow(Coord { x: 3, y: 2 });
+        assert_eq!(coord_2, Coord { x: 2, y: 2 });
+
+        let mut coord_3 = Coord { x: -1, y: -1 };
+        coord_3.follow(Coord { x: -2, y: -3 });
+        assert_eq!(coord_3, Coord { x: -2, y: -2 });
+
+        let mut coord_4 = Coord { x: -1, y: -1 };
+        coord_4.follow(Coord { x: 1, y: -2 });
+        assert_eq!(coord_4, Coord { x: 0, y: -2 });
+    }
+
+    #[test]
+    fn test_visits() {
+        let mut rope = Rope::new(3);
+        let mut visits = Visits::new(&rope);
+        for _ in 0..4 {
+            rope.step(Dir::U);
+            visits.record(&rope);
+        }
+        assert_eq!(
+            rope.knots,
+            vec![(0, 4), (0, 3), (0, 2)]
+                .into_iter()
+                .map(|(x, y)| Coord { x, y })
+                .collect::<Vec<_>>()
+        );
+        // The tail sat at the start until the rope straightened out.
+        assert_eq!(visits.knots[2][&Coord::default()], 3);
+        assert_eq!(visits.knots[0].len(), 5);
+    }
+
+    #[test]
+    fn test_follow_always_touches() {
+        // A knot only ever falls up to two cells behind on each axis, since
+        // the knot ahead of it moves at most one step (diagonally or not).
+        let mut rng = Rng::new(9);
+        for _ in 0..1000 {
+            let mut knot = Coord {
+                x: rng.range(-100, 100),
+                y: rng.range(-100, 100),
+            };
+            let ahead = knot
+                + Coord {
+                    x: rng.range(-2, 2),
+                    y: rng.range(-2, 2),
+                };
+            knot.follow(ahead);
+            assert!(knot.touching(ahead), "{:?} doesn't touch {:?}", knot, ahead);
+        }
+    }
+
+    #[test]
+    fn test_rope_stays_together() {
+        for seed in 0..20 {
+            let mut rng = Rng::new(seed);
+            let input = generate::generate(9, Size::new(100), &mut rng).unwrap();
+            let mut rope = Rope::new(rng.range(1, 12) as usize);
+            for (direction, steps) in parse_moves(&input).unwrap() {
+                for _ in 0..steps {
+                    rope.step(direction);
+                    for pair in rope.knots.windows(2) {
+                        assert!(pair[1].touching(pair[0]), "seed {}: {:?}", seed, rope.knots);
+                    }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,271 @@
+// A faster rope simulation for long move lists. Rather than stepping every knot
+// one unit at a time, each knot's path is worked out from the path of the knot
+// in front of it, a whole straight run at a time.
+//
+// This works because when the knot ahead moves in a straight line, the knot
+// behind settles into moving the same way within a couple of steps. Once the
+// gap between them stops changing, the rest of the run can be skipped.
+use super::rope::{Coord, Dir};
+use std::collections::{BTreeMap, HashMap};
+
+// Where a knot started, and its moves as (change per step, number of steps).
+// Steps where the knot doesn't move are left out.
+pub struct Path {
+    pub start: Coord,
+    pub runs: Vec<(Coord, i64)>,
+}
+
+impl Path {
+    pub fn from_moves(moves: &[(Dir, i64)]) -> Path {
+        let mut path = Path {
+            start: Coord::default(),
+            runs: Vec::new(),
+        };
+        for (direction, steps) in moves {
+            path.push(direction.delta(), *steps);
+        }
+        path
+    }
+
+    fn push(&mut self, delta: Coord, steps: i64) {
+        if steps == 0 {
+            return;
+        }
+        match self.runs.last_mut() {
+            Some((last, count)) if *last == delta => *count += steps,
+            _ => self.runs.push((delta, steps)),
+        }
+    }
+
+    // The path of a knot which starts at the same place and follows this one.
+    pub fn follower(&self) -> Path {
+        let mut path = Path {
+            start: self.start,
+            runs: Vec::new(),
+        };
+        let mut leader = self.start;
+        let mut knot = self.start;
+        for (delta, steps) in &self.runs {
+            let mut remaining = *steps;
+            while remaining > 0 {
+                let gap = leader - knot;
+                let before = knot;
+                leader = leader + *delta;
+                knot.follow(leader);
+                remaining -= 1;
+                path.push(knot - before, 1);
+
+                // Moving the same way as the leader, so the rest of the run is
+                // more of the same.
+                if leader - knot == gap {
+                    leader = leader + *delta * remaining;
+                    knot = knot + *delta * remaining;
+                    path.push(*delta, remaining);
+                    remaining = 0;
+                }
+            }
+        }
+        path
+    }
+
+    // The number of distinct cells visited along the path, without visiting
+    // them one by one.
+    pub fn distinct_cells(&self) -> u64 {
+        let mut lines = Lines::default();
+        lines.add(self.start, Coord::default(), 0);
+        let mut position = self.start;
+        for (delta, steps) in &self.runs {
+            lines.add(position, *delta, *steps);
+            position = position + *delta * *steps;
+        }
+        lines.count()
+    }
+}
+
+// Every step is horizontal, vertical, or diagonal, so a path is a set of
+// intervals on four families of lines. Each line is identified by a key, and
+// positions along it by a parameter:
+//
+//   family      key     parameter
+//   horizontal  y       x
+//   vertical    x       y
+//   diagonal    x - y   x
+//   antidiag    x + y   x
+const FAMILIES: usize = 4;
+
+fn family_of(delta: Coord) -> usize {
+    match (delta.x, delta.y) {
+        (_, 0) => 0,
+        (0, _) => 1,
+        (dx, dy) if dx == dy => 2,
+        _ => 3,
+    }
+}
+
+fn key_of(family: usize, p: Coord) -> i64 {
+    match family {
+        0 => p.y,
+        1 => p.x,
+        2 => p.x - p.y,
+        _ => p.x + p.y,
+    }
+}
+
+fn param_of(family: usize, p: Coord) -> i64 {
+    match family {
+        1 => p.y,
+        _ => p.x,
+    }
+}
+
+fn point(family: usize, key: i64, t: i64) -> Coord {
+    match family {
+        0 => Coord { x: t, y: key },
+        1 => Coord { x: key, y: t },
+        2 => Coord { x: t, y: t - key },
+        _ => Coord { x: t, y: key - t },
+    }
+}
+
+#[derive(Default)]
+struct Lines {
+    // For each family, the intervals covered on each line.
+    lines: [BTreeMap<i64, Vec<(i64, i64)>>; FAMILIES],
+}
+
+impl Lines {
+    // Adds the cells from `start` to `start + delta * steps`.
+    fn add(&mut self, start: Coord, delta: Coord, steps: i64) {
+        let family = family_of(delta);
+        let end = start + delta * steps;
+        let (a, b) = (param_of(family, start), param_of(family, end));
+        self.lines[family]
+            .entry(key_of(family, start))
+            .or_default()
+            .push((a.min(b), a.max(b)));
+    }
+
+    fn count(mut self) -> u64 {
+        // Merge overlapping intervals, so each line covers each cell once.
+        for family in &mut self.lines {
+            for intervals in family.values_mut() {
+                intervals.sort_unstable();
+                let mut merged: Vec<(i64, i64)> = Vec::with_capacity(intervals.len());
+                for (lo, hi) in intervals.drain(..) {
+                    match merged.last_mut() {
+                        Some((_, last_hi)) if lo <= *last_hi + 1 => *last_hi = (*last_hi).max(hi),
+                        _ => merged.push((lo, hi)),
+                    }
+                }
+                *intervals = merged;
+            }
+        }
+
+        let mut total: u64 = self
+            .lines
+            .iter()
+            .flat_map(|family| family.values())
+            .flatten()
+            .map(|(lo, hi)| (hi - lo + 1) as u64)
+            .sum();
+
+        // Cells where lines from different families cross are counted once per
+        // family, so find them and take off the extras.
+        let mut crossings: HashMap<Coord, u8> = HashMap::new();
+        for f in 0..FAMILIES {
+            for g in f + 1..FAMILIES {
+                for (key, intervals) in &self.lines[f] {
+                    for (lo, hi) in intervals {
+                        self.cross(f, *key, (*lo, *hi), g, &mut crossings);
+                    }
+                }
+            }
+        }
+        for families in crossings.values() {
+            total -= families.count_ones() as u64 - 1;
+        }
+        total
+    }
+
+    // Records every cell where an interval on line `key` of family `f` meets
+    // an interval of family `g`.
+    fn cross(
+        &self,
+        f: usize,
+        key: i64,
+        (lo, hi): (i64, i64),
+        g: usize,
+        crossings: &mut HashMap<Coord, u8>,
+    ) {
+        // Moving along the line changes the other family's key at a constant
+        // rate, so the lines it meets have keys in a contiguous range.
+        let base = key_of(g, point(f, key, 0));
+        let slope = key_of(g, point(f, key, 1)) - base;
+        let (k1, k2) = (base + slope * lo, base + slope * hi);
+        for (other_key, intervals) in self.lines[g].range(k1.min(k2)..=k1.max(k2)) {
+            if (other_key - base) % slope != 0 {
+                continue;
+            }
+            let p = point(f, key, (other_key - base) / slope);
+            let t = param_of(g, p);
+            let i = intervals.partition_point(|(_, other_hi)| *other_hi < t);
+            if intervals.get(i).is_some_and(|(other_lo, _)| *other_lo <= t) {
+                *crossings.entry(p).or_insert(0) |= (1 << f) | (1 << g);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::rope::{self, Rope};
+    use super::*;
+    use std::collections::HashSet;
+
+    // Step-by-step simulation to compare against.
+    fn tail_cells(moves: &[(Dir, i64)], num_knots: usize) -> usize {
+        let mut rope = Rope::new(num_knots);
+        let mut visited = HashSet::from([rope.tail()]);
+        for (direction, steps) in moves {
+            for _ in 0..*steps {
+                rope.step(*direction);
+                visited.insert(rope.tail());
+            }
+        }
+        visited.len()
+    }
+
+    fn fast_tail_cells(moves: &[(Dir, i64)], num_knots: usize) -> u64 {
+        let mut path = Path::from_moves(moves);
+        for _ in 1..num_knots {
+            path = path.follower();
+        }
+        path.distinct_cells()
+    }
+
+    #[test]
+    fn test_matches_stepping() {
+        let example = rope::parse_moves("R 5\nU 8\nL 8\nD 3\nR 17\nD 10\nL 25\nU 20").unwrap();
+        assert_eq!(fast_tail_cells(&example, 10), 36);
+
+        // A wandering rope crosses its own path plenty of times.
+        let dirs = [Dir::R, Dir::U, Dir::L, Dir::D, Dir::U, Dir::R];
+        let moves: Vec<(Dir, i64)> = (0..300)
+            .map(|i: i64| (dirs[(i * 7 % 11) as usize % dirs.len()], (i * 13) % 17))
+            .collect();
+        for num_knots in [1, 2, 3, 10, 25] {
+            assert_eq!(
+                fast_tail_cells(&moves, num_knots),
+                tail_cells(&moves, num_knots) as u64,
+                "{} knots",
+                num_knots
+            );
+        }
+    }
+
+    #[test]
+    fn test_long_moves() {
+        let moves = [(Dir::R, 1_000_000_000), (Dir::U, 1_000_000_000)];
+        assert_eq!(fast_tail_cells(&moves, 10), 1_999_999_983);
+    }
+}
@@ -0,0 +1,104 @@
+// Fuzz targets for each day. Every target runs the parse step on whatever it's
+// given, then solves both parts with run_day, and throws the results away,
+// since all that matters is that it returns (with an error is fine) rather
+// than panicking. A made-up input can legitimately take forever to solve, so
+// only inputs small enough to solve quickly are solved.
+use super::generate::{self, Size};
+use super::{day1, day10, day11, day12, day2, day3, day4, day5, day6, day7, day8, day9};
+use crate::fuzz::{Parser, Target};
+use crate::helpers::Args;
+use crate::parsing::{self, Pattern};
+use crate::rng::Rng;
+
+pub fn targets() -> Vec<Target> {
+    let parsers: [(u16, Parser); 12] = [
+        (1, |input| {
+            day1::Elves::new(input).try_for_each(|elf| elf.map(|_| ()))
+        }),
+        (2, |input| {
+            day2::parse_guide(input, &['A', 'B', 'C'], &['X', 'Y', 'Z']).map(|_| ())
+        }),
+        (3, |input| day3::parse_rucksacks(input).map(|_| ())),
+        (4, |input| {
+            let pair_pattern = Pattern::new("{a_start}-{a_end},{b_start}-{b_end}");
+            for (i, line) in input.lines().enumerate() {
+                if !line.trim().is_empty() {
+                    day4::parse_pair(&pair_pattern, line, i + 1)?;
+                }
+            }
+            Ok(())
+        }),
+        (5, |input| {
+            let [drawing, procedure] = parsing::sections::<2>(input)?;
+            let mut stacks = day5::drawing::parse(&drawing)?;
+            let command_pattern = Pattern::new("move {count} from {from} to {to}");
+            for (line_num, command) in procedure.numbered_lines() {
+                let command = day5::str_to_command(&command_pattern, command, line_num)?;
+                // Moves that can't be done are errors, which is fine.
+                let _ = day5::crane::apply(&day5::crane::CrateMover9000, &mut stacks, command);
+            }
+            Ok(())
+        }),
+        (6, |input| {
+            day6::find_markers(input.as_bytes(), 4, |_| ())
+                .expect("reading from memory can't fail");
+            Ok(())
+        }),
+        (7, |input| day7::transcript::replay(input).map(|_| ())),
+        (8, |input| {
+            let _ = day8::parse_forest(input, false);
+            day8::parse_forest(input, true).map(|_| ())
+        }),
+        (9, |input| day9::rope::parse_moves(input).map(|_| ())),
+        (10, |input| day10::cpu::parse_program(input).map(|_| ())),
+        (11, |input| day11::input_to_monkeys(input).map(|_| ())),
+        (12, |input| day12::get_map(input).map(|_| ())),
+    ];
+
+    parsers
+        .into_iter()
+        .map(|(day, parse)| {
+            // Start from a few generated inputs of different sizes, on top of
+            // the stored examples.
+            let seeds = [1, 3, 10]
+                .into_iter()
+                .filter_map(|size| {
+                    generate::generate(day, Size::new(size), &mut Rng::new(size as u64))
+                })
+                .collect();
+            let run = move |input: &str| {
+                parse(input)?;
+                if worth_solving(day, input) {
+                    for part_two in [false, true] {
+                        super::run_day(day, input, part_two, &Args::default())?;
+                    }
+                }
+                Ok(())
+            };
+            Target::new(format!("day{:02}", day), run, seeds)
+        })
+        .collect()
+}
+
+// Longer inputs are only parsed. This is plenty to hold every kind of line a
+// day has, and keeps a few thousand runs down to seconds.
+const MAX_SOLVE_LEN: usize = 2000;
+
+// Whether an input is small enough to solve quickly. For most days that's
+// down to its length, but some days take as long as their numbers say.
+fn worth_solving(day: u16, input: &str) -> bool {
+    if input.len() > MAX_SOLVE_LEN {
+        return false;
+    }
+    match day {
+        // The rope takes every step one at a time.
+        9 => day9::rope::parse_moves(input).is_ok_and(|moves| {
+            moves
+                .iter()
+                .map(|(_, steps)| (*steps).max(0) as u128)
+                .sum::<u128>()
+                <= 100_000
+        }),
+        _ => true,
+    }
+}
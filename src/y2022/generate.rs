@@ -0,0 +1,473 @@
+// Makes up puzzle inputs, for seeing how the solutions cope with inputs much
+// bigger (or stranger) than the real ones. Every input can be solved for both
+// parts of its day. Sizes too small for that are rounded up to the smallest
+// that works, e.g. at least one elf for day 1 and three monkeys for day 11.
+//
+// What the size means depends on the day: elves for day 1, rounds for day 2,
+// moves for day 9 and so on. The grid days (8 and 12) use a width and height.
+use crate::rng::Rng;
+use std::collections::HashSet;
+
+#[derive(Debug, Clone, Copy)]
+pub struct Size {
+    pub count: usize,
+    pub width: usize,
+    pub height: usize,
+}
+
+impl Size {
+    pub fn new(count: usize) -> Size {
+        Size {
+            count,
+            width: count,
+            height: count,
+        }
+    }
+
+    // About as big as the real inputs.
+    pub fn default_for(day: u16) -> Size {
+        Size::new(match day {
+            1 => 250,
+            2 => 2500,
+            3 => 300,
+            4 => 1000,
+            5 => 500,
+            6 => 4096,
+            7 => 200,
+            8 => 99,
+            9 => 2000,
+            10 => 140,
+            11 => 8,
+            12 => 41,
+            _ => 100,
+        })
+    }
+}
+
+// Returns None for days without a generator.
+pub fn generate(day: u16, size: Size, rng: &mut Rng) -> Option<String> {
+    let input = match day {
+        1 => calories(size.count, rng),
+        2 => strategy_guide(size.count, rng),
+        3 => rucksacks(size.count, rng),
+        4 => assignments(size.count, rng),
+        5 => crane_procedure(size.count, rng),
+        6 => signal(size.count, rng),
+        7 => transcript(size.count, rng),
+        8 => forest(size.width, size.height, rng),
+        9 => rope_moves(size.count, rng),
+        10 => program(size.count, rng),
+        11 => monkeys(size.count, rng),
+        12 => height_map(size.width, size.height, rng),
+        _ => return None,
+    };
+    Some(input)
+}
+
+fn lowercase(rng: &mut Rng) -> char {
+    (b'a' + rng.below(26) as u8) as char
+}
+
+fn uppercase(rng: &mut Rng) -> char {
+    (b'A' + rng.below(26) as u8) as char
+}
+
+// Each elf carries a handful of snacks.
+fn calories(elves: usize, rng: &mut Rng) -> String {
+    let elves: Vec<String> = (0..elves.max(1))
+        .map(|_| {
+            let snacks: Vec<String> = (0..rng.range(1, 15))
+                .map(|_| rng.range(1000, 60000).to_string())
+                .collect();
+            snacks.join("\n")
+        })
+        .collect();
+    elves.join("\n\n") + "\n"
+}
+
+fn strategy_guide(rounds: usize, rng: &mut Rng) -> String {
+    (0..rounds)
+        .map(|_| {
+            format!(
+                "{} {}\n",
+                rng.pick(&['A', 'B', 'C']),
+                rng.pick(&['X', 'Y', 'Z'])
+            )
+        })
+        .collect()
+}
+
+// Rucksacks in groups of three, where the only item all three carry is the
+// group's badge, and each rucksack's two compartments have exactly one item
+// in common.
+fn rucksacks(count: usize, rng: &mut Rng) -> String {
+    let letters: Vec<char> = ('a'..='z').chain('A'..='Z').collect();
+    let mut out = String::new();
+    for _ in 0..count.div_ceil(3) {
+        let badge = *rng.pick(&letters);
+        // Every other item is left out of at least one of the rucksacks.
+        let mut carried: [Vec<char>; 3] = Default::default();
+        for letter in &letters {
+            if *letter == badge {
+                continue;
+            }
+            let missing_from = rng.index(3);
+            for (i, items) in carried.iter_mut().enumerate() {
+                if i != missing_from && rng.chance(0.8) {
+                    items.push(*letter);
+                }
+            }
+        }
+
+        for mut items in carried {
+            let shared = *rng.pick(&items);
+            items.retain(|item| *item != shared);
+            // The rest are split between the compartments, so nothing else
+            // ends up in both.
+            rng.shuffle(&mut items);
+            let (left_items, right_items) = items.split_at(items.len() / 2);
+            let mut left = vec![shared];
+            let mut right = vec![shared];
+            if rng.chance(0.5) {
+                left.push(badge);
+            } else {
+                right.push(badge);
+            }
+
+            let half = rng.range(left.len().max(right.len()) as i64, 24) as usize;
+            for (compartment, others) in [(&mut left, left_items), (&mut right, right_items)] {
+                while compartment.len() < half {
+                    compartment.push(if others.is_empty() {
+                        shared
+                    } else {
+                        *rng.pick(others)
+                    });
+                }
+                rng.shuffle(compartment);
+            }
+            out.extend(left.iter().chain(&right));
+            out.push('\n');
+        }
+    }
+    out
+}
+
+fn assignments(pairs: usize, rng: &mut Rng) -> String {
+    let section = |rng: &mut Rng| {
+        let start = rng.range(1, 99);
+        (start, rng.range(start, 99))
+    };
+    (0..pairs)
+        .map(|_| {
+            let (a, b) = (section(rng), section(rng));
+            format!("{}-{},{}-{}\n", a.0, a.1, b.0, b.1)
+        })
+        .collect()
+}
+
+// Nine stacks of crates, then moves which never take more crates than a stack
+// has. There's always at least one move, since an input without any isn't
+// valid.
+fn crane_procedure(moves: usize, rng: &mut Rng) -> String {
+    let moves = moves.max(1);
+    let mut heights: Vec<usize> = (0..9).map(|_| rng.range(1, 8) as usize).collect();
+    let tallest = *heights.iter().max().unwrap();
+    let mut out = String::new();
+    for level in (0..tallest).rev() {
+        let row: Vec<String> = heights
+            .iter()
+            .map(|height| {
+                if level < *height {
+                    format!("[{}]", uppercase(rng))
+                } else {
+                    "   ".to_string()
+                }
+            })
+            .collect();
+        out.push_str(&row.join(" "));
+        out.push('\n');
+    }
+    let labels: Vec<String> = (1..=9).map(|label| format!(" {} ", label)).collect();
+    out.push_str(&labels.join(" "));
+    out.push_str("\n\n");
+
+    for _ in 0..moves {
+        let from = loop {
+            let stack = rng.index(9);
+            if heights[stack] > 0 {
+                break stack;
+            }
+        };
+        let to = (from + rng.range(1, 8) as usize) % 9;
+        let count = rng.range(1, heights[from] as i64) as usize;
+        heights[from] -= count;
+        heights[to] += count;
+        out.push_str(&format!("move {} from {} to {}\n", count, from + 1, to + 1));
+    }
+    out
+}
+
+// Random letters, ending with 14 different ones so both parts find a marker.
+fn signal(length: usize, rng: &mut Rng) -> String {
+    let mut out: String = (0..length.saturating_sub(14))
+        .map(|_| lowercase(rng))
+        .collect();
+    let mut marker: Vec<char> = ('a'..='z').collect();
+    rng.shuffle(&mut marker);
+    out.extend(&marker[..14]);
+    out.push('\n');
+    out
+}
+
+// A random filesystem of about `entries` files and directories, explored one
+// directory at a time like the puzzle's transcript. The files add up to between
+// 40M and 70M, so they fit on the disk but part two has to free up some space.
+fn transcript(entries: usize, rng: &mut Rng) -> String {
+    struct Dir {
+        files: Vec<(String, u64)>,
+        dirs: Vec<(String, usize)>,
+    }
+
+    fn new_name(names: &mut HashSet<String>, rng: &mut Rng) -> String {
+        loop {
+            let mut name: String = (0..rng.range(1, 8)).map(|_| lowercase(rng)).collect();
+            if rng.chance(0.4) {
+                name.push('.');
+                name.extend((0..3).map(|_| lowercase(rng)));
+            }
+            if names.insert(name.clone()) {
+                return name;
+            }
+        }
+    }
+
+    let mut dirs = vec![Dir {
+        files: Vec::new(),
+        dirs: Vec::new(),
+    }];
+    let mut names: Vec<HashSet<String>> = vec![HashSet::new()];
+    // File sizes start out as weights, and are scaled to the total at the end.
+    let mut num_files = 0;
+    for i in 0..entries.max(1) {
+        let parent = rng.index(dirs.len());
+        let name = new_name(&mut names[parent], rng);
+        // Part two needs at least one file, so the last entry is always one.
+        if rng.chance(0.25) && (num_files > 0 || i + 1 < entries) {
+            dirs.push(Dir {
+                files: Vec::new(),
+                dirs: Vec::new(),
+            });
+            names.push(HashSet::new());
+            let child = dirs.len() - 1;
+            dirs[parent].dirs.push((name, child));
+        } else {
+            dirs[parent]
+                .files
+                .push((name, rng.range(1, 1_000_000) as u64));
+            num_files += 1;
+        }
+    }
+
+    // Rounding down loses less than one per file, and rounding 0 up to 1 adds
+    // at most one, so the total stays close to the target.
+    let target = rng.range(42_000_000, 68_000_000) as u64;
+    let total_weight: u64 = dirs
+        .iter()
+        .flat_map(|dir| &dir.files)
+        .map(|(_, weight)| weight)
+        .sum();
+    for dir in &mut dirs {
+        for (_, size) in &mut dir.files {
+            *size = (*size * target / total_weight).max(1);
+        }
+    }
+
+    fn explore(dirs: &[Dir], dir: usize, out: &mut String) {
+        out.push_str("$ ls\n");
+        for (name, _) in &dirs[dir].dirs {
+            out.push_str(&format!("dir {}\n", name));
+        }
+        for (name, size) in &dirs[dir].files {
+            out.push_str(&format!("{} {}\n", size, name));
+        }
+        for (name, child) in &dirs[dir].dirs {
+            out.push_str(&format!("$ cd {}\n", name));
+            explore(dirs, *child, out);
+            out.push_str("$ cd ..\n");
+        }
+    }
+    let mut out = "$ cd /\n".to_string();
+    explore(&dirs, 0, &mut out);
+    out
+}
+
+fn forest(width: usize, height: usize, rng: &mut Rng) -> String {
+    (0..height.max(1))
+        .map(|_| {
+            let row: String = (0..width.max(1))
+                .map(|_| char::from_digit(rng.below(10) as u32, 10).unwrap())
+                .collect();
+            row + "\n"
+        })
+        .collect()
+}
+
+fn rope_moves(moves: usize, rng: &mut Rng) -> String {
+    (0..moves)
+        .map(|_| format!("{} {}\n", rng.pick(&['U', 'D', 'L', 'R']), rng.range(1, 20)))
+        .collect()
+}
+
+fn program(instructions: usize, rng: &mut Rng) -> String {
+    (0..instructions)
+        .map(|_| {
+            if rng.chance(0.3) {
+                "noop\n".to_string()
+            } else {
+                format!("addx {}\n", rng.range(-20, 20))
+            }
+        })
+        .collect()
+}
+
+// At most 12 monkeys, since part two multiplies all their divisors together
+// and that has to fit in a u64. Like the real inputs, one monkey squares the
+// worry level and a couple multiply it. Part one's worry levels only stay
+// small if the squaring happens once, so nobody throws to that monkey, which
+// takes at least three monkeys.
+fn monkeys(count: usize, rng: &mut Rng) -> String {
+    let count = count.clamp(3, 12);
+    let mut divisors = [2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37];
+    rng.shuffle(&mut divisors);
+    let squarer = rng.index(count);
+    let mut multipliers = 0;
+
+    let monkeys: Vec<String> = (0..count)
+        .map(|monkey| {
+            let items: Vec<String> = (0..rng.range(1, 8))
+                .map(|_| rng.range(50, 99).to_string())
+                .collect();
+            let operation = if monkey == squarer {
+                "old * old".to_string()
+            } else if multipliers < 2 && rng.chance(0.3) {
+                multipliers += 1;
+                format!("old * {}", rng.range(2, 19))
+            } else {
+                format!("old + {}", rng.range(1, 8))
+            };
+            // Two different monkeys, neither of them this one or the squaring
+            // one, unless there's only one to choose from.
+            let others: Vec<usize> = (0..count)
+                .filter(|other| *other != monkey && *other != squarer)
+                .collect();
+            let true_to = *rng.pick(&others);
+            let false_to = if others.len() > 1 {
+                loop {
+                    let other = *rng.pick(&others);
+                    if other != true_to {
+                        break other;
+                    }
+                }
+            } else {
+                true_to
+            };
+            format!(
+                "Monkey {}:\n  Starting items: {}\n  Operation: new = {}\n  Test: divisible by {}\n    If true: throw to monkey {}\n    If false: throw to monkey {}\n",
+                monkey,
+                items.join(", "),
+                operation,
+                divisors[monkey],
+                true_to,
+                false_to
+            )
+        })
+        .collect();
+    monkeys.join("\n")
+}
+
+// Random heights, with a path climbing from S to E laid through them so there's
+// always a way up. The path wanders right and down from the top left corner
+// when the grid is big enough for it to climb from a to z that way, and snakes
+// back and forth across every row otherwise. Climbing a to z takes at least 26
+// cells, so smaller grids are made taller.
+fn height_map(width: usize, height: usize, rng: &mut Rng) -> String {
+    let width = width.max(1);
+    let height = height.max(26_usize.div_ceil(width));
+    let mut grid: Vec<Vec<char>> = (0..height)
+        .map(|_| (0..width).map(|_| lowercase(rng)).collect())
+        .collect();
+
+    let path: Vec<(usize, usize)> = if width + height - 2 >= 25 {
+        let (mut x, mut y) = (0, 0);
+        let mut path = vec![(x, y)];
+        while (x, y) != (width - 1, height - 1) {
+            if y == height - 1 || (x < width - 1 && rng.chance(0.5)) {
+                x += 1;
+            } else {
+                y += 1;
+            }
+            path.push((x, y));
+        }
+        path
+    } else {
+        (0..height)
+            .flat_map(|y| {
+                (0..width).map(move |x| {
+                    if y % 2 == 0 {
+                        (x, y)
+                    } else {
+                        (width - 1 - x, y)
+                    }
+                })
+            })
+            .collect()
+    };
+
+    // Climb evenly along the path, so no step goes up by more than one.
+    let steps = path.len() - 1;
+    for (i, (x, y)) in path.iter().enumerate() {
+        grid[*y][*x] = (b'a' + (i * 25 / steps) as u8) as char;
+    }
+    let (start, end) = (path[0], path[steps]);
+    grid[start.1][start.0] = 'S';
+    grid[end.1][end.0] = 'E';
+
+    grid.into_iter()
+        .map(|row| row.into_iter().collect::<String>() + "\n")
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::helpers::Args;
+    use crate::y2022;
+
+    #[test]
+    fn test_generated_inputs_solve() {
+        for day in 1..=12 {
+            for size in [0, 1, 2, 5, 40] {
+                for seed in 1..=3 {
+                    let input = generate(day, Size::new(size), &mut Rng::new(seed)).unwrap();
+                    for part_two in [false, true] {
+                        match y2022::run_day(day, &input, part_two, &Args::default()) {
+                            Ok(answer) if answer.is_solved() => (),
+                            result => panic!(
+                                "day {} part two {} seed {} size {}: {:?}\n{}",
+                                day, part_two, seed, size, result, input
+                            ),
+                        }
+                    }
+                }
+            }
+        }
+        assert!(generate(25, Size::new(1), &mut Rng::new(1)).is_none());
+    }
+
+    #[test]
+    fn test_generators_are_seeded() {
+        let input = |seed| generate(7, Size::new(30), &mut Rng::new(seed)).unwrap();
+        assert_eq!(input(5), input(5));
+        assert_ne!(input(5), input(6));
+    }
+}
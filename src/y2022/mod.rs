@@ -1,5 +1,11 @@
+use crate::answer::Answer;
+use crate::helpers::Args;
+use crate::parsing::ParseResult;
 use std::process;
 
+pub mod fuzz;
+pub mod generate;
+
 // Note: avoiding the newline before run_day allows us to easily insert new days
 // with a bash script.
 mod day1;
@@ -14,22 +20,22 @@ mod day9;
 mod day10;
 mod day11;
 mod day12;
-pub fn run_day(day: u16, input: &String, part_two: bool) -> String {
+pub fn run_day(day: u16, input: &str, part_two: bool, args: &Args) -> ParseResult<Answer> {
     match day {
-        1 => day1::elves_by_food(input, part_two),
-        2 => day2::get_score_from_elf_rps_game(input, part_two),
-        3 => day3::rucksack_calculations(input, part_two),
-        4 => day4::assignment_checker(input, part_two),
-        5 => day5::crate_rearrangement(input, part_two),
-        6 => day6::fix_comms_device(input, part_two),
-        7 => day7::directory_sizes(input, part_two),
-        8 => day8::check_tree_visibility(input, part_two),
-        9 => day9::rope_shenanigans(input, part_two),
-        10 => day10::signal_strength(input, part_two),
-        11 => day11::day_11(input, part_two),
-        12 => day12::path_finding(input, part_two),
+        1 => day1::elves_by_food(input, part_two, args),
+        2 => day2::get_score_from_elf_rps_game(input, part_two, args),
+        3 => day3::rucksack_calculations(input, part_two, args),
+        4 => day4::assignment_checker(input, part_two, args),
+        5 => day5::crate_rearrangement(input, part_two, args),
+        6 => day6::fix_comms_device(input, part_two, args),
+        7 => day7::directory_sizes(input, part_two, args),
+        8 => day8::check_tree_visibility(input, part_two, args),
+        9 => day9::rope_shenanigans(input, part_two, args),
+        10 => day10::signal_strength(input, part_two, args),
+        11 => day11::day_11(input, part_two, args),
+        12 => day12::path_finding(input, part_two, args),
         _ => {
-            println!("Day {} not implemented yet", day);
+            eprintln!("Day {} not implemented yet", day);
             process::exit(1)
         }
     }